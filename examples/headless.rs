@@ -0,0 +1,27 @@
+// Plays a scenario without a window and prints the results as JSON, run with:
+// cargo run --release --example headless -- assets/scenarios/open_field.ron
+
+use std::{env, process};
+
+use tower_defense::headless::{self, Scenario};
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: headless <scenario.ron>");
+            process::exit(2);
+        }
+    };
+
+    match Scenario::load(&path).and_then(|scenario| headless::run(&scenario)) {
+        Ok(report) => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("reports are always valid JSON")
+        ),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// Headless stress test for targeting and collision, run with:
+// cargo run --release --example spatial_benchmark
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use tower_defense::{
+    grid::{agent::GridAgent, GridPlugin},
+    health::{
+        damage::{DamageKind, Resistances},
+        Damageable, HealthPlugin,
+    },
+    lifetime::LifetimePlugin,
+    shooting::{bullet::BulletShooter, weapon::Weapon, Shooter, ShootingPlugin, Targetable},
+    simulation::SimulationPlugin,
+    spatial::SpatialPlugin,
+    state::GameState,
+};
+
+const ENEMIES: usize = 1000;
+const TOWERS: usize = 100;
+const FRAMES: usize = 600;
+
+// small deterministic generator so every run spawns the same layout
+struct XorShift(u32);
+
+impl XorShift {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32
+    }
+}
+
+fn spawn_entities(mut commands: Commands) {
+    let mut rng = XorShift(0x2545f491);
+
+    for _ in 0..ENEMIES {
+        let pos = Vec3::new(rng.next() * 1280. - 640., rng.next() * 720. - 360., 10.);
+
+        commands.spawn((
+            TransformBundle::from_transform(
+                Transform::from_translation(pos).with_scale(Vec3::new(10., 10., 1.)),
+            ),
+            GridAgent {
+                path: Some(vec![(63, (rng.next() * 35.) as i32)]),
+                speed: 50.,
+                base_speed: 50.,
+                error_margin: 0.5,
+            },
+            Targetable::default(),
+            Damageable {
+                max_health: f32::MAX,
+                health: f32::MAX,
+                resistances: Resistances::default(),
+                armor: 0.,
+                regeneration: 0.,
+                shield: None,
+            },
+        ));
+    }
+
+    for i in 0..TOWERS {
+        let pos = Vec3::new(
+            (i % 10) as f32 * 128. - 576.,
+            (i / 10) as f32 * 72. - 324.,
+            1.,
+        );
+
+        commands.spawn((
+            TransformBundle::from_transform(
+                Transform::from_translation(pos).with_scale(Vec3::new(15., 15., 1.)),
+            ),
+            Shooter {
+                target: None,
+                range: 300.,
+                lead: true,
+            },
+            Weapon::new(Duration::from_millis(250)),
+            BulletShooter {
+                damage: 10.,
+                kind: DamageKind::Physical,
+            },
+        ));
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(GameState::Playing)
+        .add_plugin(SimulationPlugin {
+            steps_per_second: 60,
+            seed: 0,
+            lockstep: true,
+        })
+        .add_plugin(ShootingPlugin)
+        .add_plugin(LifetimePlugin)
+        .add_plugin(GridPlugin {
+            debug: false,
+            cell_size: 20.,
+            map_file_path: "assets/full_size.map".to_string(),
+        })
+        .add_plugin(HealthPlugin {
+            debug: false,
+            health_bars: false,
+        })
+        .add_plugin(SpatialPlugin { cell_size: 50. })
+        .add_startup_system(spawn_entities);
+
+    // first update runs the startup systems
+    app.update();
+
+    let mut frame_times = Vec::with_capacity(FRAMES);
+    for _ in 0..FRAMES {
+        let start = Instant::now();
+        app.update();
+        frame_times.push(start.elapsed());
+    }
+
+    frame_times.sort();
+    let total: Duration = frame_times.iter().sum();
+
+    println!("{ENEMIES} enemies, {TOWERS} towers, {FRAMES} frames");
+    println!("mean:   {:?}", total / FRAMES as u32);
+    println!("median: {:?}", frame_times[FRAMES / 2]);
+    println!("p99:    {:?}", frame_times[FRAMES * 99 / 100]);
+    println!("max:    {:?}", frame_times[FRAMES - 1]);
+}
@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    enemy::{self, Enemy, EnemyKind},
+    grid::agent::GridAgent,
+    health::{Damageable, HealthSystem, Invulnerable},
+    shooting::{Disabled, Shooter},
+    simulation::SimulationApp,
+    state::GameState,
+};
+
+const BAR_WIDTH: f32 = 400.;
+const BAR_HEIGHT: f32 = 14.;
+
+#[derive(Clone, Copy)]
+pub enum Ability {
+    SetSpeed(f32), // multiplier for the archetype's speed
+    SpawnMinions { kind: EnemyKind, count: u32 },
+    Invulnerable(Duration),
+    DisableTowers { radius: f32, duration: Duration },
+}
+
+#[derive(Clone)]
+pub struct Phase {
+    pub threshold: f32, // share of max health at or below which the phase starts
+    pub abilities: Vec<Ability>,
+}
+
+#[derive(Component)]
+pub struct Boss {
+    pub phases: Vec<Phase>, // ordered from the highest threshold to the lowest
+    next_phase: usize,
+}
+
+impl Boss {
+    pub fn new(phases: Vec<Phase>) -> Self {
+        Self {
+            phases,
+            next_phase: 0,
+        }
+    }
+}
+
+#[derive(Component)]
+struct BossBar;
+
+#[derive(Component)]
+struct BossBarFill;
+
+struct Caster {
+    entity: Entity,
+    kind: EnemyKind,
+    position: Vec3,
+}
+
+fn cast(
+    commands: &mut Commands,
+    shooters: &Query<(Entity, &Transform), With<Shooter>>,
+    caster: &Caster,
+    agent: &mut GridAgent,
+    ability: Ability,
+) {
+    let position = caster.position;
+
+    match ability {
+        Ability::SetSpeed(multiplier) => {
+            // keep whatever slows are running on top of the new base speed
+            let base_speed = caster.kind.archetype().speed * multiplier;
+            if agent.base_speed > 0. {
+                agent.speed *= base_speed / agent.base_speed;
+            }
+            agent.base_speed = base_speed;
+        }
+        Ability::SpawnMinions { kind, count } => {
+            for i in 0..count {
+                let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                let offset = (Vec2::from_angle(angle) * 12.).extend(0.);
+
+                enemy::spawn(commands, kind, position + offset, agent.path.clone());
+            }
+        }
+        Ability::Invulnerable(duration) => {
+            commands.entity(caster.entity).insert(Invulnerable {
+                timer: Timer::new(duration, TimerMode::Once),
+            });
+        }
+        Ability::DisableTowers { radius, duration } => {
+            // towers aren't in the spatial index, there are few enough to check them all
+            for (tower, tower_transform) in shooters.iter() {
+                let distance = tower_transform
+                    .translation
+                    .truncate()
+                    .distance(position.truncate());
+
+                // towers and bosses sit on different layers, only the distance on the map counts
+                if distance <= radius {
+                    commands.entity(tower).insert(Disabled {
+                        timer: Timer::new(duration, TimerMode::Once),
+                    });
+                }
+            }
+        }
+    }
+}
+
+type BossState<'a> = (
+    Entity,
+    &'a mut Boss,
+    &'a Enemy,
+    &'a Damageable,
+    &'a Transform,
+    &'a mut GridAgent,
+);
+
+// start every phase whose threshold the boss' health dropped to
+fn enter_phases(
+    mut commands: Commands,
+    mut bosses: Query<BossState>,
+    shooters: Query<(Entity, &Transform), With<Shooter>>,
+) {
+    for (entity, mut boss, enemy, damageable, transform, mut agent) in bosses.iter_mut() {
+        if damageable.is_dead() {
+            continue;
+        }
+
+        let health_share = damageable.health / damageable.max_health;
+        let caster = Caster {
+            entity,
+            kind: enemy.kind,
+            position: transform.translation,
+        };
+
+        while let Some(phase) = boss.phases.get(boss.next_phase).cloned() {
+            if health_share > phase.threshold {
+                break;
+            }
+
+            for ability in phase.abilities {
+                cast(&mut commands, &shooters, &caster, &mut agent, ability);
+            }
+            boss.next_phase += 1;
+        }
+    }
+}
+
+fn setup_boss_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Boss Bar"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(12.),
+                        left: Val::Percent(50.),
+                        ..default()
+                    },
+                    margin: UiRect {
+                        left: Val::Px(-BAR_WIDTH / 2.),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(BAR_WIDTH), Val::Px(BAR_HEIGHT)),
+                    ..default()
+                },
+                background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            },
+            BossBar,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                        ..default()
+                    },
+                    background_color: Color::CRIMSON.into(),
+                    ..default()
+                },
+                BossBarFill,
+            ));
+        });
+}
+
+fn despawn_boss_bar(mut commands: Commands, bars: Query<Entity, With<BossBar>>) {
+    for entity in bars.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// tracks the boss with the least health left when several are alive
+fn update_boss_bar(
+    bosses: Query<&Damageable, With<Boss>>,
+    mut bar: Query<&mut Visibility, With<BossBar>>,
+    mut fill: Query<&mut Style, With<BossBarFill>>,
+) {
+    let health_share = bosses
+        .iter()
+        .map(|damageable| (damageable.health / damageable.max_health).clamp(0., 1.))
+        .reduce(f32::min);
+
+    for mut visibility in bar.iter_mut() {
+        visibility.is_visible = health_share.is_some();
+    }
+
+    if let Some(health_share) = health_share {
+        for mut style in fill.iter_mut() {
+            style.size.width = Val::Percent(health_share * 100.);
+        }
+    }
+}
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(GameState::Playing).with_system(setup_boss_bar))
+            .add_simulation_system_set(
+                SystemSet::new().with_system(
+                    enter_phases
+                        .after(HealthSystem::Heal)
+                        .after(enemy::split_on_death)
+                        .before(HealthSystem::Death),
+                ),
+            )
+            .add_system_set(SystemSet::on_update(GameState::Playing).with_system(update_boss_bar))
+            .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(despawn_boss_bar));
+    }
+}
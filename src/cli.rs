@@ -0,0 +1,302 @@
+use std::fs;
+
+use crate::{grid::a_star::util::get_map_size, screens::LEVELS};
+
+pub const USAGE: &str = "\
+Usage: tower-defense [OPTIONS]
+
+Options:
+  --level <NUMBER|NAME>   start straight into a level from the level select
+  --map <FILE>            start straight into a level on a .map file
+  --cell-size <PIXELS>    size of a grid cell, 20 by default
+  --debug-grid            draw the pathfinding grid
+  --debug-health          show health debug info
+  --width <PIXELS>        window width, 1280 by default
+  --height <PIXELS>       window height, 720 by default
+  --seed <NUMBER>         seed for everything random in a level
+  --headless <SCENARIO>   play a scenario file without a window and print the report as JSON
+  -h, --help              print this message";
+
+// everything about the level and the window, which a headless scenario already decides
+const PLAY_ONLY: [&str; 7] = [
+    "--level",
+    "--map",
+    "--cell-size",
+    "--debug-grid",
+    "--debug-health",
+    "--width",
+    "--height",
+];
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Play,
+    Headless(String), // scenario file
+    Help,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub command: Command,
+    pub map_file_path: String,
+    pub cell_size: f32,
+    pub skip_menu: bool, // a level or map was picked, so the menus aren't needed
+    pub debug_grid: bool,
+    pub debug_health: bool,
+    pub width: f32,
+    pub height: f32,
+    pub seed: Option<u64>, // headless runs fall back to the scenario's own seed
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            command: Command::Play,
+            map_file_path: LEVELS[0].map_file_path.to_string(),
+            cell_size: LEVELS[0].cell_size,
+            skip_menu: false,
+            debug_grid: false,
+            debug_health: false,
+            width: 1280.,
+            height: 720.,
+            seed: None,
+        }
+    }
+}
+
+fn value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, String> {
+    match args.next() {
+        Some(value) if !value.starts_with("--") => Ok(value),
+        _ => Err(format!("{} needs a value", flag)),
+    }
+}
+
+fn positive(value: &str, flag: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(number) if number.is_finite() && number > 0. => Ok(number),
+        _ => Err(format!("{} must be a positive number, got {}", flag, value)),
+    }
+}
+
+// levels are numbered from 1 like on the level select screen, names ignore case
+fn find_level(level: &str) -> Result<(String, f32), String> {
+    let found = match level.parse::<usize>() {
+        Ok(number) => number.checked_sub(1).and_then(|index| LEVELS.get(index)),
+        Err(_) => LEVELS
+            .iter()
+            .find(|candidate| candidate.name.eq_ignore_ascii_case(level)),
+    };
+
+    match found {
+        Some(found) => Ok((found.map_file_path.to_string(), found.cell_size)),
+        None => {
+            let names: Vec<_> = LEVELS
+                .iter()
+                .enumerate()
+                .map(|(i, level)| format!("{} ({})", i + 1, level.name))
+                .collect();
+            Err(format!(
+                "unknown level {}, pick one of: {}",
+                level,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+impl Options {
+    // the arguments after the program name
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        let mut level = None;
+        let mut map = None;
+        let mut cell_size = None;
+        let mut play_only = Vec::new();
+
+        while let Some(arg) = args.next() {
+            if PLAY_ONLY.contains(&arg.as_str()) {
+                play_only.push(arg.clone());
+            }
+
+            match arg.as_str() {
+                "--level" => level = Some(value(&mut args, &arg)?),
+                "--map" => map = Some(value(&mut args, &arg)?),
+                "--cell-size" => cell_size = Some(positive(&value(&mut args, &arg)?, &arg)?),
+                "--debug-grid" => options.debug_grid = true,
+                "--debug-health" => options.debug_health = true,
+                "--width" => options.width = positive(&value(&mut args, &arg)?, &arg)?,
+                "--height" => options.height = positive(&value(&mut args, &arg)?, &arg)?,
+                "--seed" => {
+                    let seed = value(&mut args, &arg)?;
+                    options.seed = Some(seed.parse().map_err(|_| {
+                        format!("--seed must be a whole number from 0 up, got {}", seed)
+                    })?);
+                }
+                "--headless" => options.command = Command::Headless(value(&mut args, &arg)?),
+                "-h" | "--help" => options.command = Command::Help,
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+
+        if let Command::Headless(_) = options.command {
+            if !play_only.is_empty() {
+                return Err(format!(
+                    "--headless plays the scenario without a window, drop {}",
+                    play_only.join(", ")
+                ));
+            }
+            return Ok(options);
+        }
+
+        match (level, map) {
+            (Some(_), Some(_)) => return Err("pick either --level or --map, not both".to_string()),
+            (Some(level), None) => {
+                let (map_file_path, level_cell_size) = find_level(&level)?;
+                options.map_file_path = map_file_path;
+                options.cell_size = level_cell_size;
+                options.skip_menu = true;
+            }
+            (None, Some(map)) => {
+                options.map_file_path = map;
+                options.skip_menu = true;
+            }
+            (None, None) => {}
+        }
+
+        if let Some(cell_size) = cell_size {
+            options.cell_size = cell_size;
+        }
+
+        // spawning the grid panics on a bad map, so it's checked before the window opens
+        if options.command == Command::Play {
+            let map = fs::read_to_string(&options.map_file_path)
+                .map_err(|err| format!("could not read map {}: {}", options.map_file_path, err))?;
+            let (width, height) = get_map_size(&map);
+            if width == 0 || height == 0 {
+                return Err(format!("map {} is empty", options.map_file_path));
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn defaults_to_the_menu_on_the_first_level() {
+        let options = parse(&[]).unwrap();
+
+        assert_eq!(options.command, Command::Play);
+        assert_eq!(options.map_file_path, LEVELS[0].map_file_path);
+        assert!(!options.skip_menu);
+    }
+
+    #[test]
+    fn rejects_an_unknown_flag() {
+        assert_eq!(parse(&["--frob"]).unwrap_err(), "unknown argument --frob");
+    }
+
+    #[test]
+    fn rejects_a_missing_value() {
+        assert_eq!(parse(&["--seed"]).unwrap_err(), "--seed needs a value");
+        assert_eq!(
+            parse(&["--map", "--debug-grid"]).unwrap_err(),
+            "--map needs a value"
+        );
+    }
+
+    #[test]
+    fn rejects_both_a_level_and_a_map() {
+        let err = parse(&["--level", "1", "--map", "assets/sample.map"]).unwrap_err();
+
+        assert_eq!(err, "pick either --level or --map, not both");
+    }
+
+    #[test]
+    fn rejects_level_and_window_flags_with_headless() {
+        let err = parse(&["--headless", "scenario.ron", "--map", "assets/sample.map"]).unwrap_err();
+        assert!(err.contains("--map"), "{}", err);
+
+        let err = parse(&[
+            "--headless",
+            "scenario.ron",
+            "--debug-grid",
+            "--debug-health",
+            "--width",
+            "800",
+            "--height",
+            "600",
+        ])
+        .unwrap_err();
+        for flag in ["--debug-grid", "--debug-health", "--width", "--height"] {
+            assert!(err.contains(flag), "{}", err);
+        }
+    }
+
+    #[test]
+    fn headless_keeps_the_seed() {
+        let options = parse(&["--headless", "scenario.ron", "--seed", "7"]).unwrap();
+
+        assert_eq!(
+            options.command,
+            Command::Headless("scenario.ron".to_string())
+        );
+        assert_eq!(options.seed, Some(7));
+    }
+
+    #[test]
+    fn rejects_sizes_that_are_not_positive() {
+        for width in ["0", "-5", "wide"] {
+            let err = parse(&["--width", width]).unwrap_err();
+            assert_eq!(
+                err,
+                format!("--width must be a positive number, got {}", width)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_a_seed_that_is_not_a_whole_number() {
+        for seed in ["abc", "-1", "1.5"] {
+            assert!(parse(&["--seed", seed]).is_err(), "{}", seed);
+        }
+    }
+
+    #[test]
+    fn finds_a_level_by_number_or_name() {
+        for level in ["1", "Open Field", "open field"] {
+            let options = parse(&["--level", level]).unwrap();
+
+            assert_eq!(options.map_file_path, LEVELS[0].map_file_path);
+            assert_eq!(options.cell_size, LEVELS[0].cell_size);
+            assert!(options.skip_menu);
+        }
+    }
+
+    #[test]
+    fn rejects_an_unknown_level() {
+        for level in ["0", "10", "Closed Field"] {
+            let err = parse(&["--level", level]).unwrap_err();
+            assert!(err.starts_with("unknown level"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn rejects_an_unreadable_map() {
+        let err = parse(&["--map", "assets/missing.map"]).unwrap_err();
+
+        assert!(
+            err.starts_with("could not read map assets/missing.map"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GameSpeed {
+    Stopped,
+    #[default]
+    Normal,
+    Double,
+    Quadruple,
+}
+
+impl GameSpeed {
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            GameSpeed::Stopped => 0.,
+            GameSpeed::Normal => 1.,
+            GameSpeed::Double => 2.,
+            GameSpeed::Quadruple => 4.,
+        }
+    }
+}
+
+// most steps a single frame catches up on, so a long hitch doesn't stall the game further
+const MAX_STEPS_PER_FRAME: u32 = 8;
+
+// gameplay time, advanced in fixed steps by the simulation; UI and camera keep using `Time`
+#[derive(Resource)]
+pub struct GameClock {
+    speed: GameSpeed,
+    step: Duration,
+    accumulated: Duration, // real time scaled by the speed, not simulated yet
+    elapsed: Duration,
+    lockstep: bool,
+}
+
+impl GameClock {
+    pub fn new(step: Duration, lockstep: bool) -> Self {
+        Self {
+            speed: GameSpeed::default(),
+            step,
+            accumulated: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            lockstep,
+        }
+    }
+
+    pub fn speed(&self) -> GameSpeed {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: GameSpeed) {
+        self.speed = speed;
+    }
+
+    // always a whole step, gameplay never sees partial ones
+    pub fn delta(&self) -> Duration {
+        self.step
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.step.as_secs_f32()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    // how far the frame is between the last step and the next one
+    pub fn alpha(&self) -> f32 {
+        if self.lockstep {
+            return 1.;
+        }
+
+        (self.accumulated.as_secs_f32() / self.step.as_secs_f32()).min(1.)
+    }
+
+    pub(crate) fn accumulate(&mut self, real_delta: Duration) {
+        if self.lockstep {
+            // one step per update at normal speed, regardless of how long the update took
+            self.accumulated = self.step * self.speed.multiplier() as u32;
+        } else {
+            let max = self.step * MAX_STEPS_PER_FRAME;
+            self.accumulated =
+                (self.accumulated + real_delta.mul_f32(self.speed.multiplier())).min(max);
+        }
+    }
+
+    pub(crate) fn consume_step(&mut self) -> bool {
+        if self.accumulated < self.step {
+            return false;
+        }
+
+        self.accumulated -= self.step;
+        self.elapsed += self.step;
+        true
+    }
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self::new(Duration::from_secs_f64(1. / 60.), false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a power of two fraction of a second, so scaling by the speed stays exact
+    const STEP: Duration = Duration::from_micros(15_625);
+
+    fn steps(clock: &mut GameClock) -> u32 {
+        let mut steps = 0;
+        while clock.consume_step() {
+            steps += 1;
+        }
+        steps
+    }
+
+    #[test]
+    fn accumulates_real_time_scaled_by_speed() {
+        let mut clock = GameClock::new(STEP, false);
+        clock.set_speed(GameSpeed::Double);
+
+        clock.accumulate(STEP * 2);
+
+        assert_eq!(steps(&mut clock), 4);
+        assert_eq!(clock.elapsed(), STEP * 4);
+    }
+
+    #[test]
+    fn keeps_the_remainder_for_the_next_frame() {
+        let mut clock = GameClock::new(STEP, false);
+
+        clock.accumulate(STEP / 2);
+        assert_eq!(steps(&mut clock), 0);
+
+        clock.accumulate(STEP / 2);
+        assert_eq!(steps(&mut clock), 1);
+    }
+
+    #[test]
+    fn clamps_a_long_frame() {
+        let mut clock = GameClock::new(STEP, false);
+        clock.set_speed(GameSpeed::Quadruple);
+
+        clock.accumulate(Duration::from_secs(5));
+
+        assert_eq!(steps(&mut clock), MAX_STEPS_PER_FRAME);
+    }
+
+    #[test]
+    fn lockstep_runs_the_speed_multiplier_in_steps_every_update() {
+        let speeds = [
+            (GameSpeed::Stopped, 0),
+            (GameSpeed::Normal, 1),
+            (GameSpeed::Double, 2),
+            (GameSpeed::Quadruple, 4),
+        ];
+
+        for (speed, expected) in speeds {
+            let mut clock = GameClock::new(STEP, true);
+            clock.set_speed(speed);
+
+            // how long the update really took doesn't matter
+            for real_delta in [Duration::ZERO, Duration::from_secs(5)] {
+                clock.accumulate(real_delta);
+                assert_eq!(steps(&mut clock), expected, "{:?}", speed);
+            }
+        }
+    }
+}
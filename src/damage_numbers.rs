@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    clock::GameClock,
+    health::{heal::Healed, DamageTaken, Death, HealthSystem},
+    lifetime::Lifetime,
+    simulation::{GameRng, SimulationApp, SimulationStage},
+    FONT_PATH,
+};
+
+// sideways drift, so numbers popping up on the same target don't cover each other
+const MAX_DRIFT: f32 = 15.;
+
+#[derive(Resource)]
+struct DamageNumberFont(Handle<Font>);
+
+#[derive(Component)]
+pub struct FloatingText {
+    pub velocity: Vec2,
+}
+
+struct Popup {
+    value: String,
+    font_size: f32,
+    color: Color,
+    velocity: Vec2,
+    duration: Duration,
+}
+
+impl Popup {
+    fn damage(amount: f32, critical: bool, weakness: bool) -> Self {
+        // small damage over time ticks would all round to zero otherwise
+        let value = if amount < 10. {
+            format!("{:.1}", amount)
+        } else {
+            format!("{:.0}", amount)
+        };
+
+        if critical {
+            Popup {
+                value: format!("{}!", value),
+                font_size: 18.,
+                color: Color::ORANGE,
+                velocity: Vec2::new(0., 60.),
+                duration: Duration::from_millis(800),
+            }
+        } else if weakness {
+            Popup {
+                value,
+                font_size: 14.,
+                color: Color::YELLOW,
+                velocity: Vec2::new(0., 40.),
+                duration: Duration::from_millis(600),
+            }
+        } else {
+            Popup {
+                value,
+                font_size: 12.,
+                color: Color::WHITE,
+                velocity: Vec2::new(0., 40.),
+                duration: Duration::from_millis(600),
+            }
+        }
+    }
+
+    // the whole hit went into the shield
+    fn shielded(absorbed: f32) -> Self {
+        Popup {
+            value: format!("{:.0}", absorbed.ceil()),
+            font_size: 12.,
+            color: Color::CYAN,
+            velocity: Vec2::new(0., 40.),
+            duration: Duration::from_millis(600),
+        }
+    }
+
+    fn heal(amount: f32) -> Self {
+        Popup {
+            value: format!("+{:.0}", amount.ceil()),
+            font_size: 12.,
+            color: Color::LIME_GREEN,
+            velocity: Vec2::new(0., 30.),
+            duration: Duration::from_millis(600),
+        }
+    }
+
+    fn kill() -> Self {
+        Popup {
+            value: "KILL".to_string(),
+            font_size: 20.,
+            color: Color::RED,
+            velocity: Vec2::new(0., 25.),
+            duration: Duration::from_millis(1000),
+        }
+    }
+}
+
+fn spawn_popup(
+    commands: &mut Commands,
+    rng: &mut GameRng,
+    font: &Handle<Font>,
+    position: Vec3,
+    popup: Popup,
+) {
+    let drift = Vec2::new(rng.range(-MAX_DRIFT, MAX_DRIFT), 0.);
+
+    commands.spawn((
+        Name::new("Damage Number"),
+        Text2dBundle {
+            text: Text::from_section(
+                popup.value,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: popup.font_size,
+                    color: popup.color,
+                },
+            )
+            .with_alignment(TextAlignment::CENTER),
+            transform: Transform::from_translation(position.truncate().extend(10.)),
+            ..default()
+        },
+        FloatingText {
+            velocity: popup.velocity + drift,
+        },
+        Lifetime {
+            timer: Timer::new(popup.duration, TimerMode::Once),
+        },
+    ));
+}
+
+// headless runs have no asset server and get no damage numbers
+fn load_font(mut commands: Commands, asset_server: Option<Res<AssetServer>>) {
+    if let Some(asset_server) = asset_server {
+        commands.insert_resource(DamageNumberFont(asset_server.load(FONT_PATH)));
+    }
+}
+
+fn spawn_damage_numbers(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    font: Option<Res<DamageNumberFont>>,
+    mut damage_taken: EventReader<DamageTaken>,
+    mut healed: EventReader<Healed>,
+    mut deaths: EventReader<Death>,
+    targets: Query<&Transform>,
+) {
+    let font = match font {
+        Some(font) => font.0.clone(),
+        None => return,
+    };
+
+    for event in damage_taken.iter() {
+        let popup = if event.amount > 0. {
+            Popup::damage(event.amount, event.critical, event.weakness)
+        } else if event.absorbed > 0. {
+            Popup::shielded(event.absorbed)
+        } else {
+            continue;
+        };
+
+        if let Ok(transform) = targets.get(event.target) {
+            spawn_popup(&mut commands, &mut rng, &font, transform.translation, popup);
+        }
+    }
+
+    for event in healed.iter() {
+        if let Ok(transform) = targets.get(event.target) {
+            let popup = Popup::heal(event.amount);
+            spawn_popup(&mut commands, &mut rng, &font, transform.translation, popup);
+        }
+    }
+
+    for death in deaths.iter() {
+        let popup = Popup::kill();
+        spawn_popup(&mut commands, &mut rng, &font, death.position, popup);
+    }
+}
+
+fn float_damage_numbers(
+    clock: Res<GameClock>,
+    mut numbers: Query<(&mut Transform, &mut Text, &Lifetime, &FloatingText)>,
+) {
+    for (mut transform, mut text, lifetime, floating) in numbers.iter_mut() {
+        transform.translation += (floating.velocity * clock.delta_seconds()).extend(0.);
+
+        let alpha = lifetime.timer.percent_left();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+pub struct DamageNumbersPlugin;
+
+impl Plugin for DamageNumbersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(load_font)
+            .add_simulation_system_set(
+                SystemSet::new().with_system(spawn_damage_numbers.after(HealthSystem::Heal)),
+            )
+            .add_simulation_system_to_stage(SimulationStage::Movement, float_damage_numbers);
+    }
+}
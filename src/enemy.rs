@@ -0,0 +1,353 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    boss::{Ability, Boss, Phase},
+    grid::{a_star::GridCoord, agent::GridAgent},
+    health::{
+        damage::{Resistances, Shield},
+        heal::Healer,
+        Damageable, Death, HealthSystem,
+    },
+    shooting::Targetable,
+    simulation::SimulationApp,
+    status::{StatusEffects, StatusImmunity},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum EnemyKind {
+    Grunt,
+    Runner,
+    Brute,
+    Healer,
+    Brood,
+    Shade,
+    Warlord,
+}
+
+// what an enemy breaks into when it dies
+#[derive(Clone, Copy)]
+pub struct Split {
+    pub kind: EnemyKind,
+    pub count: u32,
+}
+
+pub struct EnemyArchetype {
+    pub name: &'static str,
+    pub color: Color,
+    pub size: f32,
+    pub speed: f32,
+    pub max_health: f32,
+    pub resistances: Resistances,
+    pub armor: f32,
+    pub regeneration: f32,
+    pub shield: Option<Shield>,
+    pub immunity: StatusImmunity,
+    pub healer: Option<Healer>,
+    pub split: Option<Split>,
+    pub stealth: bool,
+    pub phases: Vec<Phase>, // empty unless it is a boss
+    pub bounty: u32,        // gold for killing it
+}
+
+impl EnemyKind {
+    pub fn archetype(&self) -> EnemyArchetype {
+        match self {
+            EnemyKind::Grunt => EnemyArchetype {
+                name: "Grunt",
+                color: Color::RED,
+                size: 10.,
+                speed: 50.,
+                max_health: 100.,
+                resistances: Resistances::default(),
+                armor: 0.,
+                regeneration: 0.,
+                shield: None,
+                immunity: StatusImmunity::default(),
+                healer: None,
+                split: None,
+                stealth: false,
+                phases: Vec::new(),
+                bounty: 5,
+            },
+            EnemyKind::Runner => EnemyArchetype {
+                name: "Runner",
+                color: Color::PINK,
+                size: 8.,
+                speed: 90.,
+                max_health: 60.,
+                resistances: Resistances {
+                    physical: -0.25,
+                    ice: -0.5,
+                    ..default()
+                },
+                armor: 0.,
+                regeneration: 0.,
+                shield: Some(Shield::new(30., 15., Duration::from_secs(2))),
+                immunity: StatusImmunity {
+                    poison: true,
+                    ..default()
+                },
+                healer: None,
+                split: None,
+                stealth: false,
+                phases: Vec::new(),
+                bounty: 4,
+            },
+            EnemyKind::Brute => EnemyArchetype {
+                name: "Brute",
+                color: Color::MAROON,
+                size: 14.,
+                speed: 30.,
+                max_health: 250.,
+                resistances: Resistances {
+                    physical: 0.5,
+                    explosive: -0.25,
+                    fire: 0.25,
+                    ..default()
+                },
+                armor: 5.,
+                regeneration: 2.,
+                shield: None,
+                immunity: StatusImmunity {
+                    stun: true,
+                    ..default()
+                },
+                healer: None,
+                split: None,
+                stealth: false,
+                phases: Vec::new(),
+                bounty: 12,
+            },
+            EnemyKind::Healer => EnemyArchetype {
+                name: "Healer",
+                color: Color::LIME_GREEN,
+                size: 9.,
+                speed: 45.,
+                max_health: 80.,
+                resistances: Resistances::default(),
+                armor: 0.,
+                regeneration: 0.,
+                shield: None,
+                immunity: StatusImmunity::default(),
+                healer: Some(Healer::new(80., 15., Duration::from_secs(2))),
+                split: None,
+                stealth: false,
+                phases: Vec::new(),
+                bounty: 8,
+            },
+            EnemyKind::Brood => EnemyArchetype {
+                name: "Brood",
+                color: Color::PURPLE,
+                size: 13.,
+                speed: 35.,
+                max_health: 150.,
+                resistances: Resistances::default(),
+                armor: 0.,
+                regeneration: 0.,
+                shield: None,
+                immunity: StatusImmunity::default(),
+                healer: None,
+                split: Some(Split {
+                    kind: EnemyKind::Runner,
+                    count: 3,
+                }),
+                stealth: false,
+                phases: Vec::new(),
+                bounty: 10,
+            },
+            EnemyKind::Shade => EnemyArchetype {
+                name: "Shade",
+                color: Color::rgba(0.7, 0.7, 0.9, 0.35),
+                size: 10.,
+                speed: 60.,
+                max_health: 70.,
+                resistances: Resistances::default(),
+                armor: 0.,
+                regeneration: 0.,
+                shield: None,
+                immunity: StatusImmunity::default(),
+                healer: None,
+                split: None,
+                stealth: true,
+                phases: Vec::new(),
+                bounty: 8,
+            },
+            EnemyKind::Warlord => EnemyArchetype {
+                name: "Warlord",
+                color: Color::ORANGE_RED,
+                size: 22.,
+                speed: 25.,
+                max_health: 1500.,
+                resistances: Resistances {
+                    ice: 0.25,
+                    ..default()
+                },
+                armor: 3.,
+                regeneration: 0.,
+                shield: None,
+                immunity: StatusImmunity {
+                    stun: true,
+                    ..default()
+                },
+                healer: None,
+                split: None,
+                stealth: false,
+                phases: vec![
+                    Phase {
+                        threshold: 0.75,
+                        abilities: vec![Ability::SpawnMinions {
+                            kind: EnemyKind::Grunt,
+                            count: 4,
+                        }],
+                    },
+                    Phase {
+                        threshold: 0.5,
+                        abilities: vec![
+                            Ability::Invulnerable(Duration::from_secs(3)),
+                            Ability::DisableTowers {
+                                radius: 200.,
+                                duration: Duration::from_secs(4),
+                            },
+                        ],
+                    },
+                    Phase {
+                        threshold: 0.25,
+                        abilities: vec![
+                            Ability::SetSpeed(2.),
+                            Ability::SpawnMinions {
+                                kind: EnemyKind::Runner,
+                                count: 3,
+                            },
+                        ],
+                    },
+                ],
+                bounty: 100,
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Enemy {
+    pub kind: EnemyKind,
+}
+
+#[derive(Bundle)]
+pub struct EnemyBundle {
+    pub name: Name,
+    #[bundle]
+    pub sprite: SpriteBundle,
+    pub agent: GridAgent,
+    pub targetable: Targetable,
+    pub damageable: Damageable,
+    pub enemy: Enemy,
+    pub status_effects: StatusEffects,
+    pub immunity: StatusImmunity,
+}
+
+impl EnemyBundle {
+    pub fn new(kind: EnemyKind, pos: Vec3, path: Option<Vec<GridCoord>>) -> Self {
+        let archetype = kind.archetype();
+
+        Self {
+            name: Name::new(archetype.name),
+            sprite: SpriteBundle {
+                sprite: Sprite {
+                    color: archetype.color,
+                    ..default()
+                },
+                transform: Transform::from_translation(pos).with_scale(Vec3::new(
+                    archetype.size,
+                    archetype.size,
+                    1.,
+                )),
+                ..default()
+            },
+            agent: GridAgent {
+                path,
+                speed: archetype.speed,
+                base_speed: archetype.speed,
+                error_margin: 0.5,
+            },
+            targetable: Targetable {
+                stealth: archetype.stealth,
+            },
+            damageable: Damageable {
+                max_health: archetype.max_health,
+                health: archetype.max_health,
+                resistances: archetype.resistances,
+                armor: archetype.armor,
+                regeneration: archetype.regeneration,
+                shield: archetype.shield,
+            },
+            enemy: Enemy { kind },
+            status_effects: StatusEffects::default(),
+            immunity: archetype.immunity,
+        }
+    }
+}
+
+// spawns the bundle along with the components only some archetypes have
+pub fn spawn(
+    commands: &mut Commands,
+    kind: EnemyKind,
+    pos: Vec3,
+    path: Option<Vec<GridCoord>>,
+) -> Entity {
+    let archetype = kind.archetype();
+    let mut enemy = commands.spawn(EnemyBundle::new(kind, pos, path));
+
+    if let Some(healer) = archetype.healer {
+        enemy.insert(healer);
+    }
+
+    if !archetype.phases.is_empty() {
+        enemy.insert(Boss::new(archetype.phases));
+    }
+
+    enemy.id()
+}
+
+// runs before the dead are despawned, so the parent's remaining path can still be read
+pub(crate) fn split_on_death(
+    mut commands: Commands,
+    mut deaths: EventReader<Death>,
+    enemies: Query<(&Enemy, &GridAgent)>,
+) {
+    const SPREAD: f32 = 6.;
+
+    for death in deaths.iter() {
+        if let Ok((enemy, agent)) = enemies.get(death.entity) {
+            if let Some(split) = enemy.kind.archetype().split {
+                for i in 0..split.count {
+                    let angle = i as f32 / split.count as f32 * std::f32::consts::TAU;
+                    let offset = (Vec2::from_angle(angle) * SPREAD).extend(0.);
+
+                    spawn(
+                        &mut commands,
+                        split.kind,
+                        death.position + offset,
+                        agent.path.clone(),
+                    );
+                }
+            }
+        }
+    }
+}
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_simulation_system_set(
+            SystemSet::new().with_system(
+                split_on_death
+                    .after(HealthSystem::Apply)
+                    .before(HealthSystem::Death),
+            ),
+        );
+    }
+}
@@ -1,16 +1,29 @@
 use bevy::prelude::*;
 
+use crate::clock::GameClock;
+
 use super::{a_star::GridCoord, DebugGrid};
 
 #[derive(Component)]
 pub struct GridAgent {
     pub path: Option<Vec<GridCoord>>,
     pub speed: f32,
+    pub base_speed: f32,   // speed without slows and the like
     pub error_margin: f32, // how much further from the waypoint it can go
 }
 
+impl GridAgent {
+    // velocity the agent currently moves with, zero if it has nowhere to go
+    pub fn velocity(&self, grid: &DebugGrid, current_pos: &Vec3) -> Vec2 {
+        match self.path.as_ref().and_then(|path| path.first()) {
+            Some(next_waypoint) => direction_towards(grid, next_waypoint, current_pos) * self.speed,
+            None => Vec2::ZERO,
+        }
+    }
+}
+
 pub fn follow_path(
-    time: Res<Time>,
+    clock: Res<GameClock>,
     mut agents: Query<(&mut Transform, &mut GridAgent)>,
     grid: Query<&DebugGrid>,
 ) {
@@ -18,6 +31,7 @@ pub fn follow_path(
 
     for (mut transform, mut agent) in agents.iter_mut() {
         let error_margin = agent.error_margin;
+        let step = agent.speed * clock.delta_seconds();
 
         if let Some(path) = &mut agent.path {
             let next_waypoint = path.get(0);
@@ -30,14 +44,16 @@ pub fn follow_path(
 
                 if reached_waypoint(&transform.translation, &target_pos, error_margin) {
                     path.remove(0);
-                    return;
+                    continue;
                 }
 
-                let direction =
-                    direction_towards(&grid, next_waypoint, &transform.translation) * agent.speed;
-
-                transform.translation = transform.translation
-                    + Vec3::new(direction.x, direction.y, 0.) * time.delta_seconds();
+                // land on the waypoint instead of overshooting it back and forth
+                if transform.translation.truncate().distance(target_pos) <= step {
+                    transform.translation = target_pos.extend(transform.translation.z);
+                } else {
+                    let direction = direction_towards(grid, next_waypoint, &transform.translation);
+                    transform.translation += (direction * step).extend(0.);
+                }
             }
         }
     }
@@ -54,5 +70,6 @@ fn direction_towards(grid: &DebugGrid, next_waypoint: &(i32, i32), current_pos:
     let direction = grid.to_screen_coords(next_waypoint.0 as usize, next_waypoint.1 as usize)
         - Vec2::new(current_pos.x, current_pos.y);
 
-    direction.normalize()
+    // zero while standing right on the waypoint
+    direction.normalize_or_zero()
 }
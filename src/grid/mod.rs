@@ -5,7 +5,11 @@ use std::fs;
 
 use bevy::prelude::*;
 
-use crate::grid::a_star::util::MapNodeType;
+use crate::{
+    grid::a_star::util::MapNodeType,
+    simulation::{SimulationApp, SimulationStage},
+    state::GameState,
+};
 
 use a_star::Grid;
 
@@ -42,7 +46,7 @@ pub struct DebugGrid {
     pub size_x: usize,
     pub size_y: usize,
     cells_offset: Vec2,
-    map_file_path: &'static str,
+    map_file_path: String,
 }
 
 #[derive(Component)]
@@ -53,7 +57,7 @@ pub struct DebugNode {
 }
 
 impl DebugGrid {
-    fn new(position: Vec2, cell_size: f32, size_x: usize, size_y: usize, map_file_path: &'static str) -> Self {
+    fn new(position: Vec2, cell_size: f32, size_x: usize, size_y: usize, map_file_path: String) -> Self {
         Self {
             position,
             cell_size,
@@ -102,14 +106,27 @@ impl DebugGrid {
 
     pub fn find_path(&self, start: GridCoord, end: GridCoord) -> Option<Vec<GridCoord>> {
         // TODO: optimization - don't create the grid every time, rethink
-        let mut a_star_grid = Grid::from(self.map_file_path);
+        let mut a_star_grid = Grid::from(self.map_file_path.as_str());
 
         a_star_grid.astar(start, end, true)
     }
 }
 
-fn spawn_grid(mut commands: Commands, debug: bool, cell_size: f32, map_file_path: &'static str) {
-    let map_str = fs::read_to_string(map_file_path).expect("Could not read .map file");
+// the level select screen changes the map before a level starts
+#[derive(Resource, Clone)]
+pub struct GridSettings {
+    pub debug: bool,
+    pub cell_size: f32,
+    pub map_file_path: String,
+}
+
+fn spawn_grid(mut commands: Commands, settings: Res<GridSettings>) {
+    let GridSettings {
+        debug,
+        cell_size,
+        map_file_path,
+    } = settings.clone();
+    let map_str = fs::read_to_string(&map_file_path).expect("Could not read .map file");
     let (width, height) = a_star::util::get_map_size(&map_str);
     let map_grid = a_star::util::load_map_matrix(map_str);
     let grid = DebugGrid::new(Vec2::new(0., 0.), cell_size, width, height, map_file_path);
@@ -137,6 +154,14 @@ fn spawn_grid(mut commands: Commands, debug: bool, cell_size: f32, map_file_path
     commands.spawn((grid, Name::new("Grid")));
 }
 
+type GridEntity = Or<(With<DebugGrid>, With<DebugNode>)>;
+
+fn despawn_grid(mut commands: Commands, grid: Query<Entity, GridEntity>) {
+    for entity in grid.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
 fn color_nodes(mut nodes: Query<(&mut Sprite, &DebugNode)>) {
     for (mut sprite, node) in nodes.iter_mut() {
         sprite.color = node.color;
@@ -146,22 +171,22 @@ fn color_nodes(mut nodes: Query<(&mut Sprite, &DebugNode)>) {
 pub struct GridPlugin {
     pub debug: bool,
     pub cell_size: f32,
-    pub map_file_path: &'static str,
+    pub map_file_path: String,
 }
 
 impl Plugin for GridPlugin {
     fn build(&self, app: &mut App) {
-        let debug = self.debug;
-        let cell_size = self.cell_size;
-        let map_file_path = self.map_file_path;
-
-        app.add_startup_system_to_stage(StartupStage::PreStartup, move |commands: Commands| {
-            spawn_grid(commands, debug, cell_size, map_file_path)
+        app.insert_resource(GridSettings {
+            debug: self.debug,
+            cell_size: self.cell_size,
+            map_file_path: self.map_file_path.clone(),
         })
-        .add_system(follow_path);
+        .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(spawn_grid))
+        .add_simulation_system_to_stage(SimulationStage::Movement, follow_path)
+        .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(despawn_grid));
 
-        if debug {
-            app.add_system(color_nodes);
+        if self.debug {
+            app.add_system_set(SystemSet::on_update(GameState::Playing).with_system(color_nodes));
             // .add_system(find_and_color_path);
         }
     }
@@ -0,0 +1,210 @@
+use std::fs;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    boss::BossPlugin,
+    clock::GameClock,
+    enemy::EnemyPlugin,
+    grid::{a_star::util::get_map_size, GridPlugin},
+    health::HealthPlugin,
+    lifetime::LifetimePlugin,
+    score::{DamageDealt, Score, ScorePlugin},
+    shooting::ShootingPlugin,
+    simulation::{GameRng, SimulationPlugin},
+    spatial::SpatialPlugin,
+    state::{GameState, Lives, Outcome, StatePlugin},
+    status::StatusPlugin,
+    tower::{TowerKind, TowerLayout, TowerPlacement, TowerPlugin},
+    wave::{WavePlugin, WaveScript},
+};
+
+const STEPS_PER_SECOND: u32 = 60;
+
+fn default_cell_size() -> f32 {
+    20.
+}
+
+fn default_time_limit() -> f32 {
+    1800.
+}
+
+// a level to play from start to finish without a window, for balancing runs
+#[derive(Deserialize, Debug)]
+pub struct Scenario {
+    pub map_file_path: String,
+    #[serde(default = "default_cell_size")]
+    pub cell_size: f32,
+    pub wave_file_path: String,
+    pub towers: Vec<TowerPlacement>,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default = "default_time_limit")]
+    pub time_limit: f32, // game seconds after which the run is cut off
+}
+
+impl Scenario {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let source = fs::read_to_string(path)
+            .map_err(|err| format!("could not read scenario {}: {}", path, err))?;
+
+        ron::from_str(&source).map_err(|err| format!("invalid scenario {}: {}", path, err))
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RunResult {
+    Victory,
+    Defeat,
+    TimedOut,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct TowerReport {
+    pub kind: TowerKind,
+    pub x: usize,
+    pub y: usize,
+    pub damage: f32,
+}
+
+#[derive(Resource, Serialize, PartialEq, Debug)]
+pub struct Report {
+    pub result: RunResult,
+    pub seed: u64,
+    pub seconds: f32, // game time the level took
+    pub lives: u32,
+    pub leaks: u32,
+    pub kills: u32,
+    pub gold: u32,
+    pub towers: Vec<TowerReport>,
+}
+
+// runs before the level is cleaned up, while the towers are still around
+fn write_report(
+    mut commands: Commands,
+    outcome: Option<Res<Outcome>>,
+    clock: Res<GameClock>,
+    rng: Res<GameRng>,
+    lives: Res<Lives>,
+    score: Res<Score>,
+    towers: Query<(Entity, &TowerPlacement, &DamageDealt)>,
+) {
+    // in the order they were placed
+    let mut towers: Vec<_> = towers.iter().collect();
+    towers.sort_by_key(|(entity, _, _)| *entity);
+
+    let result = match outcome.map(|outcome| *outcome) {
+        Some(Outcome::Victory) => RunResult::Victory,
+        Some(Outcome::Defeat) => RunResult::Defeat,
+        None => RunResult::TimedOut,
+    };
+
+    commands.insert_resource(Report {
+        result,
+        seed: rng.seed(),
+        seconds: clock.elapsed().as_secs_f32(),
+        lives: lives.0,
+        leaks: score.leaks,
+        kills: score.kills,
+        gold: score.gold,
+        towers: towers
+            .into_iter()
+            .map(|(_, placement, dealt)| TowerReport {
+                kind: placement.kind,
+                x: placement.x,
+                y: placement.y,
+                damage: dealt.0,
+            })
+            .collect(),
+    });
+}
+
+// spawning the grid panics on a bad map, so it's checked up front
+fn validate(scenario: &Scenario) -> Result<(), String> {
+    if !scenario.cell_size.is_finite() || scenario.cell_size <= 0. {
+        return Err(format!(
+            "cell size must be a positive number, got {}",
+            scenario.cell_size
+        ));
+    }
+
+    let map = fs::read_to_string(&scenario.map_file_path)
+        .map_err(|err| format!("could not read map {}: {}", scenario.map_file_path, err))?;
+    let (width, height) = get_map_size(&map);
+    if width == 0 || height == 0 {
+        return Err(format!("map {} is empty", scenario.map_file_path));
+    }
+
+    for placement in scenario.towers.iter() {
+        if placement.x >= width || placement.y >= height {
+            return Err(format!(
+                "{:?} tower at ({}, {}) is outside of the {}x{} map",
+                placement.kind, placement.x, placement.y, width, height
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// plays the scenario as fast as possible, every run with the same seed ends up the same
+pub fn run(scenario: &Scenario) -> Result<Report, String> {
+    validate(scenario)?;
+    let wave = WaveScript::load(&scenario.wave_file_path)?;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(SimulationPlugin {
+            steps_per_second: STEPS_PER_SECOND,
+            seed: scenario.seed,
+            lockstep: true,
+        })
+        .add_plugin(StatePlugin)
+        .add_plugin(ShootingPlugin)
+        .add_plugin(LifetimePlugin)
+        .add_plugin(GridPlugin {
+            debug: false,
+            cell_size: scenario.cell_size,
+            map_file_path: scenario.map_file_path.clone(),
+        })
+        .add_plugin(HealthPlugin {
+            debug: false,
+            health_bars: false,
+        })
+        .add_plugin(SpatialPlugin { cell_size: 50. })
+        .add_plugin(StatusPlugin)
+        .add_plugin(EnemyPlugin)
+        .add_plugin(BossPlugin)
+        .add_plugin(TowerPlugin)
+        .add_plugin(WavePlugin)
+        .add_plugin(ScorePlugin)
+        .insert_resource(TowerLayout(scenario.towers.clone()))
+        .insert_resource(wave)
+        .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(write_report));
+
+    // the first update enters the main menu, which goes straight to the level without screens
+    app.update();
+    app.world
+        .resource_mut::<State<GameState>>()
+        .set(GameState::Playing)
+        .map_err(|err| format!("could not start the level: {:?}", err))?;
+
+    while !app.world.contains_resource::<Report>() {
+        if app.world.resource::<GameClock>().elapsed().as_secs_f32() >= scenario.time_limit {
+            // already ending if this fails
+            let _ = app
+                .world
+                .resource_mut::<State<GameState>>()
+                .set(GameState::GameOver);
+        }
+
+        app.update();
+    }
+
+    match app.world.remove_resource::<Report>() {
+        Some(report) => Ok(report),
+        None => Err("the level ended without a report".to_string()),
+    }
+}
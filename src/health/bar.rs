@@ -0,0 +1,87 @@
+use bevy::{ecs::schedule::ShouldRun, prelude::*};
+
+use crate::state::GameState;
+
+use super::Damageable;
+
+const BAR_WIDTH: f32 = 16.;
+const BAR_HEIGHT: f32 = 3.;
+const BAR_GAP: f32 = 4.; // space between the top of the damageable and the bar
+
+#[derive(Resource)]
+pub struct HealthBarSettings {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+pub struct HealthBar;
+
+// with bars turned off, as in headless runs, no bar entities are spawned at all
+pub(super) fn show_health_bars(
+    settings: Res<HealthBarSettings>,
+    state: Res<State<GameState>>,
+) -> ShouldRun {
+    if settings.enabled && *state.current() == GameState::Playing {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+fn bar_color(ratio: f32) -> Color {
+    if ratio > 0.6 {
+        Color::GREEN
+    } else if ratio > 0.3 {
+        Color::YELLOW
+    } else {
+        Color::RED
+    }
+}
+
+pub(super) fn attach_health_bars(
+    mut commands: Commands,
+    damageables: Query<Entity, Added<Damageable>>,
+) {
+    for entity in damageables.iter() {
+        let bar = commands
+            .spawn((
+                Name::new("Health Bar"),
+                SpriteBundle {
+                    visibility: Visibility { is_visible: false },
+                    ..default()
+                },
+                HealthBar,
+            ))
+            .id();
+
+        commands.entity(entity).add_child(bar);
+    }
+}
+
+pub(super) fn update_health_bars(
+    mut bars: Query<(&Parent, &mut Transform, &mut Sprite, &mut Visibility), With<HealthBar>>,
+    damageables: Query<(&Damageable, &Transform), Without<HealthBar>>,
+) {
+    for (parent, mut transform, mut sprite, mut visibility) in bars.iter_mut() {
+        if let Ok((damageable, parent_transform)) = damageables.get(parent.get()) {
+            let ratio = (damageable.health / damageable.max_health).clamp(0., 1.);
+            visibility.is_visible = ratio < 1.;
+
+            if !visibility.is_visible {
+                continue;
+            }
+
+            // the bar inherits the parent's scale, undo it to keep the bar the same size on every enemy
+            let parent_scale = parent_transform.scale;
+            let width = BAR_WIDTH * ratio;
+
+            sprite.color = bar_color(ratio);
+            transform.scale = Vec3::new(width / parent_scale.x, BAR_HEIGHT / parent_scale.y, 1.);
+            transform.translation = Vec3::new(
+                -(BAR_WIDTH - width) / 2. / parent_scale.x,
+                0.5 + (BAR_GAP + BAR_HEIGHT / 2.) / parent_scale.y,
+                1.,
+            );
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::Damageable;
+
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DamageKind {
+    #[default]
+    Physical,
+    Explosive,
+    Fire,
+    Ice,
+    Lightning,
+    Poison,
+}
+
+// fraction of each damage kind that is blocked: 1 is immune, negative is a weakness
+#[derive(Reflect, Clone, Copy, Default)]
+pub struct Resistances {
+    pub physical: f32,
+    pub explosive: f32,
+    pub fire: f32,
+    pub ice: f32,
+    pub lightning: f32,
+    pub poison: f32,
+}
+
+impl Resistances {
+    pub fn get(&self, kind: DamageKind) -> f32 {
+        match kind {
+            DamageKind::Physical => self.physical,
+            DamageKind::Explosive => self.explosive,
+            DamageKind::Fire => self.fire,
+            DamageKind::Ice => self.ice,
+            DamageKind::Lightning => self.lightning,
+            DamageKind::Poison => self.poison,
+        }
+    }
+}
+
+// a hit that lands with the chance deals the multiplier times its damage
+#[derive(Clone, Copy, Debug)]
+pub struct Crit {
+    pub chance: f32,
+    pub multiplier: f32,
+}
+
+// share of a hit that always gets through armor, however thick it is
+const MIN_DAMAGE_SHARE: f32 = 0.1;
+
+// absorbs damage before health, regenerates once it hasn't been hit for a while
+#[derive(Reflect, FromReflect, Clone, Copy)]
+pub struct Shield {
+    pub max: f32,
+    pub current: f32,
+    pub regen_per_second: f32,
+    pub regen_delay: Duration,
+    since_hit: Duration,
+}
+
+impl Shield {
+    pub fn new(max: f32, regen_per_second: f32, regen_delay: Duration) -> Self {
+        Self {
+            max,
+            current: max,
+            regen_per_second,
+            regen_delay,
+            since_hit: Duration::ZERO,
+        }
+    }
+
+    // returns whatever the shield couldn't absorb
+    fn absorb(&mut self, amount: f32) -> f32 {
+        self.since_hit = Duration::ZERO;
+
+        let absorbed = amount.min(self.current);
+        self.current -= absorbed;
+        amount - absorbed
+    }
+
+    pub fn regenerate(&mut self, delta: Duration) {
+        self.since_hit += delta;
+
+        if self.since_hit >= self.regen_delay {
+            self.current =
+                (self.current + self.regen_per_second * delta.as_secs_f32()).min(self.max);
+        }
+    }
+}
+
+pub struct DamageOutcome {
+    pub absorbed: f32, // taken by the shield
+    pub health: f32,   // taken off health
+}
+
+// the only place where raw damage is turned into health loss:
+// resistances first, then the shield soaks up what it can and armor blunts the rest
+pub fn calculate_damage(
+    amount: f32,
+    kind: DamageKind,
+    damageable: &mut Damageable,
+) -> DamageOutcome {
+    let resistance = damageable.resistances.get(kind).min(1.);
+    let resisted = (amount * (1. - resistance)).max(0.);
+
+    let leftover = match damageable.shield.as_mut() {
+        Some(shield) => shield.absorb(resisted),
+        None => resisted,
+    };
+    let health = if leftover > 0. {
+        (leftover - damageable.armor).max(leftover * MIN_DAMAGE_SHARE)
+    } else {
+        0.
+    };
+
+    DamageOutcome {
+        absorbed: resisted - leftover,
+        health,
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{clock::GameClock, spatial::SpatialIndex};
+
+use super::Damageable;
+
+const REGEN_TICK: Duration = Duration::from_millis(500);
+
+// requested healing, capped at max health when applied
+pub struct HealEvent {
+    pub source: Option<Entity>, // None for passive regeneration
+    pub target: Entity,
+    pub amount: f32,
+}
+
+// health that was actually restored
+pub struct Healed {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub amount: f32,
+}
+
+// periodically heals every other damageable within the radius
+#[derive(Component, Clone)]
+pub struct Healer {
+    pub radius: f32,
+    pub amount: f32,
+    pub timer: Timer,
+}
+
+impl Healer {
+    pub fn new(radius: f32, amount: f32, interval: Duration) -> Self {
+        Self {
+            radius,
+            amount,
+            timer: Timer::new(interval, TimerMode::Repeating),
+        }
+    }
+}
+
+pub(super) fn regenerate_health(
+    clock: Res<GameClock>,
+    mut tick: Local<Option<Timer>>,
+    mut heal_events: EventWriter<HealEvent>,
+    damageables: Query<(Entity, &Damageable)>,
+) {
+    let tick = tick.get_or_insert_with(|| Timer::new(REGEN_TICK, TimerMode::Repeating));
+    let ticks = tick.tick(clock.delta()).times_finished_this_tick();
+
+    if ticks == 0 {
+        return;
+    }
+
+    for (entity, damageable) in damageables.iter() {
+        if damageable.regeneration > 0. && damageable.health < damageable.max_health {
+            heal_events.send(HealEvent {
+                source: None,
+                target: entity,
+                amount: damageable.regeneration * REGEN_TICK.as_secs_f32() * ticks as f32,
+            });
+        }
+    }
+}
+
+pub(super) fn pulse_healers(
+    clock: Res<GameClock>,
+    index: Res<SpatialIndex>,
+    mut heal_events: EventWriter<HealEvent>,
+    mut healers: Query<(Entity, &Transform, &mut Healer)>,
+    damageables: Query<&Damageable>,
+) {
+    for (healer_entity, transform, mut healer) in healers.iter_mut() {
+        let pulses = healer.timer.tick(clock.delta()).times_finished_this_tick();
+
+        if pulses == 0 {
+            continue;
+        }
+
+        for entry in index.query_radius(transform.translation.truncate(), healer.radius) {
+            if entry.entity == healer_entity {
+                continue;
+            }
+
+            let wounded = damageables
+                .get(entry.entity)
+                .is_ok_and(|damageable| damageable.health < damageable.max_health);
+
+            if wounded {
+                heal_events.send(HealEvent {
+                    source: Some(healer_entity),
+                    target: entry.entity,
+                    amount: healer.amount * pulses as f32,
+                });
+            }
+        }
+    }
+}
+
+pub(super) fn apply_healing(
+    mut heal_events: EventReader<HealEvent>,
+    mut healed: EventWriter<Healed>,
+    mut damageables: Query<&mut Damageable>,
+) {
+    for event in heal_events.iter() {
+        if let Ok(mut damageable) = damageables.get_mut(event.target) {
+            // no bringing back what died this frame
+            if damageable.is_dead() {
+                continue;
+            }
+
+            let amount = event
+                .amount
+                .min(damageable.max_health - damageable.health)
+                .max(0.);
+
+            if amount > 0. {
+                damageable.health += amount;
+                healed.send(Healed {
+                    source: event.source,
+                    target: event.target,
+                    amount,
+                });
+            }
+        }
+    }
+}
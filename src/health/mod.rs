@@ -0,0 +1,363 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    clock::GameClock,
+    simulation::{GameRng, SimulationApp},
+    spatial::SpatialIndex,
+};
+
+use self::{
+    bar::{attach_health_bars, show_health_bars, update_health_bars, HealthBarSettings},
+    heal::{apply_healing, pulse_healers, regenerate_health, HealEvent, Healed},
+    damage::{calculate_damage, Crit, DamageKind, Resistances, Shield},
+    splash::{detonate, Detonation},
+};
+
+pub mod bar;
+pub mod damage;
+pub mod heal;
+pub mod splash;
+
+#[derive(Reflect, Component)]
+pub struct Damageable {
+    pub max_health: f32,
+    pub health: f32,
+    pub resistances: Resistances,
+    pub armor: f32, // flat amount taken off every hit that reaches health
+    pub regeneration: f32, // health per second
+    pub shield: Option<Shield>,
+}
+
+impl Damageable {
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.
+    }
+}
+
+// takes no damage at all until the timer runs out
+#[derive(Component)]
+pub struct Invulnerable {
+    pub timer: Timer,
+}
+
+#[derive(Clone, Copy)]
+pub enum HitMode {
+    Single,                      // despawns on first contact
+    Pierce(u32),                 // despawns after hitting this many damageables
+    Zone { interval: Duration }, // stays around, hits everything inside once per interval
+}
+
+#[derive(Component)]
+pub struct DamageDealer {
+    pub damage: f32,
+    pub kind: DamageKind,
+    pub source: Option<Entity>, // credited with the damage, usually the tower that fired
+    pub hit_mode: HitMode,
+    pub crit: Option<Crit>,
+    hits: HashMap<Entity, Duration>, // when each damageable was last hit
+    hits_landed: u32,
+}
+
+impl DamageDealer {
+    pub fn new(damage: f32, kind: DamageKind) -> Self {
+        Self {
+            damage,
+            kind,
+            source: None,
+            hit_mode: HitMode::Single,
+            crit: None,
+            hits: HashMap::default(),
+            hits_landed: 0,
+        }
+    }
+
+    pub fn with_source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_hit_mode(mut self, hit_mode: HitMode) -> Self {
+        self.hit_mode = hit_mode;
+        self
+    }
+
+    pub fn with_crit(mut self, crit: Crit) -> Self {
+        self.crit = Some(crit);
+        self
+    }
+
+    // whether the target is outside of its hit window
+    pub fn can_hit(&self, target: Entity, now: Duration) -> bool {
+        match (self.hit_mode, self.hits.get(&target)) {
+            (_, None) => true,
+            (HitMode::Zone { interval }, Some(last_hit)) => now - *last_hit >= interval,
+            (_, Some(_)) => false,
+        }
+    }
+
+    pub fn is_spent(&self) -> bool {
+        match self.hit_mode {
+            HitMode::Single => self.hits_landed >= 1,
+            HitMode::Pierce(targets) => self.hits_landed >= targets,
+            HitMode::Zone { .. } => false,
+        }
+    }
+
+    fn register_hit(&mut self, target: Entity, now: Duration) {
+        // a long lived zone would otherwise remember every target it ever touched,
+        // despawned ones included, past their window they can be hit again anyway
+        if let HitMode::Zone { interval } = self.hit_mode {
+            self.hits.retain(|_, last_hit| now - *last_hit < interval);
+        }
+
+        self.hits.insert(target, now);
+        self.hits_landed += 1;
+    }
+}
+
+pub struct Hit {
+    pub dealer: Entity,
+    pub target: Entity,
+}
+
+// raw damage before armor, resistances and the like are taken into account
+pub struct DamageEvent {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub amount: f32,
+    pub kind: DamageKind,
+    pub crit: Option<Crit>, // rolled once the hit reaches the modify stage
+}
+
+// damage after all modifiers, exactly what gets subtracted from health
+pub struct DamageTaken {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub amount: f32,
+    pub absorbed: f32, // soaked up by the shield, not part of the amount
+    pub kind: DamageKind,
+    pub weakness: bool, // the target is weak to this kind of damage
+    pub critical: bool,
+}
+
+pub struct Death {
+    pub entity: Entity,
+    pub killer: Option<Entity>,
+    pub position: Vec3,
+}
+
+#[derive(SystemLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HealthSystem {
+    Compute,
+    Modify,
+    Apply,
+    Heal,
+    Death,
+}
+
+fn apply_damage_on_collision(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    index: Res<SpatialIndex>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut hits: EventWriter<Hit>,
+    damageables: Query<(), With<Damageable>>,
+    mut damage_dealers: Query<(Entity, &mut DamageDealer, &Transform)>,
+) {
+    let now = clock.elapsed();
+
+    for (damage_dealer_entity, mut damage_dealer, damage_dealer_transform) in
+        damage_dealers.iter_mut()
+    {
+        let damage_dealer_pos = damage_dealer_transform.translation.truncate();
+        let damage_dealer_size = damage_dealer_transform.scale.truncate();
+
+        for entry in index.query_aabb(damage_dealer_pos, damage_dealer_size) {
+            if !damageables.contains(entry.entity) || !damage_dealer.can_hit(entry.entity, now) {
+                continue;
+            }
+
+            damage_dealer.register_hit(entry.entity, now);
+            damage_events.send(DamageEvent {
+                source: damage_dealer.source,
+                target: entry.entity,
+                amount: damage_dealer.damage,
+                kind: damage_dealer.kind,
+                crit: damage_dealer.crit,
+            });
+            hits.send(Hit {
+                dealer: damage_dealer_entity,
+                target: entry.entity,
+            });
+
+            if damage_dealer.is_spent() {
+                commands.entity(damage_dealer_entity).despawn();
+                break;
+            }
+        }
+    }
+}
+
+fn modify_damage(
+    mut rng: ResMut<GameRng>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut damage_taken: EventWriter<DamageTaken>,
+    mut damageables: Query<&mut Damageable, Without<Invulnerable>>,
+) {
+    for event in damage_events.iter() {
+        if let Ok(mut damageable) = damageables.get_mut(event.target) {
+            // crits multiply the raw damage, so resistances, shields and armor still apply
+            let (amount, critical) = match event.crit {
+                Some(crit) if rng.next_f32() < crit.chance => {
+                    (event.amount * crit.multiplier, true)
+                }
+                _ => (event.amount, false),
+            };
+            let outcome = calculate_damage(amount, event.kind, &mut damageable);
+
+            damage_taken.send(DamageTaken {
+                source: event.source,
+                target: event.target,
+                amount: outcome.health,
+                absorbed: outcome.absorbed,
+                kind: event.kind,
+                weakness: damageable.resistances.get(event.kind) < 0.,
+                critical,
+            });
+        }
+    }
+}
+
+fn apply_damage(
+    mut damage_taken: EventReader<DamageTaken>,
+    mut deaths: EventWriter<Death>,
+    mut damageables: Query<(&mut Damageable, &Transform)>,
+) {
+    for event in damage_taken.iter() {
+        if let Ok((mut damageable, transform)) = damageables.get_mut(event.target) {
+            // already dead from an earlier hit this frame, don't die twice
+            if damageable.is_dead() {
+                continue;
+            }
+
+            damageable.health -= event.amount;
+
+            if damageable.is_dead() {
+                deaths.send(Death {
+                    entity: event.target,
+                    killer: event.source,
+                    position: transform.translation,
+                });
+            }
+        }
+    }
+}
+
+fn regenerate_shields(clock: Res<GameClock>, mut damageables: Query<&mut Damageable>) {
+    for mut damageable in damageables.iter_mut() {
+        if let Some(shield) = damageable.shield.as_mut() {
+            shield.regenerate(clock.delta());
+        }
+    }
+}
+
+fn expire_invulnerability(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    mut invulnerables: Query<(Entity, &mut Invulnerable)>,
+) {
+    for (entity, mut invulnerable) in invulnerables.iter_mut() {
+        if invulnerable.timer.tick(clock.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
+
+fn despawn_dead(mut commands: Commands, mut deaths: EventReader<Death>) {
+    for death in deaths.iter() {
+        commands.entity(death.entity).despawn_recursive();
+    }
+}
+
+fn print_health(damageables: Query<(&Name, &Damageable)>) {
+    println!("health tick:");
+    for (name, damageable) in damageables.iter() {
+        println!("{} - {}/{}", name, damageable.health, damageable.max_health);
+    }
+}
+
+pub struct HealthPlugin {
+    pub debug: bool,
+    pub health_bars: bool,
+}
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<DamageTaken>()
+            .add_event::<Death>()
+            .add_event::<Hit>()
+            .add_event::<Detonation>()
+            .add_event::<HealEvent>()
+            .add_event::<Healed>()
+            .add_simulation_system_set(
+                SystemSet::new()
+                    .with_system(apply_damage_on_collision.label(HealthSystem::Compute))
+                    .with_system(
+                        detonate
+                            .label(HealthSystem::Compute)
+                            .after(apply_damage_on_collision),
+                    )
+                    .with_system(
+                        modify_damage
+                            .label(HealthSystem::Modify)
+                            .after(HealthSystem::Compute),
+                    )
+                    .with_system(
+                        apply_damage
+                            .label(HealthSystem::Apply)
+                            .after(HealthSystem::Modify),
+                    )
+                    .with_system(
+                        despawn_dead
+                            .label(HealthSystem::Death)
+                            .after(HealthSystem::Apply),
+                    )
+                    .with_system(regenerate_shields.before(HealthSystem::Modify))
+                    .with_system(
+                        expire_invulnerability
+                            .after(regenerate_shields)
+                            .before(HealthSystem::Modify),
+                    )
+                    // healing only goes to what survived this step's damage
+                    .with_system(regenerate_health.after(HealthSystem::Apply))
+                    .with_system(
+                        pulse_healers
+                            .after(regenerate_health)
+                            .before(HealthSystem::Heal),
+                    )
+                    .with_system(
+                        apply_healing
+                            .label(HealthSystem::Heal)
+                            .after(HealthSystem::Apply)
+                            .before(HealthSystem::Death),
+                    ),
+            )
+            .add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(show_health_bars)
+                    .with_system(attach_health_bars)
+                    .with_system(update_health_bars),
+            )
+            .insert_resource(HealthBarSettings {
+                enabled: self.health_bars,
+            })
+            .register_type::<Damageable>();
+
+        if self.debug {
+            app.add_simulation_system_set(SystemSet::new().with_system(print_health));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+
+use crate::{lifetime::Lifetime, spatial::SpatialIndex};
+
+use super::{damage::DamageKind, DamageEvent, Damageable};
+
+#[derive(Clone, Copy)]
+pub enum Falloff {
+    None,
+    Linear,
+    Quadratic,
+}
+
+impl Falloff {
+    // share of the full damage dealt at the given distance from the center
+    pub fn factor(&self, distance: f32, radius: f32) -> f32 {
+        // a point blast only reaches what's right on it
+        if radius <= 0. {
+            return 1.;
+        }
+
+        let closeness = (1. - distance / radius).clamp(0., 1.);
+
+        match self {
+            Falloff::None => 1.,
+            Falloff::Linear => closeness,
+            Falloff::Quadratic => closeness * closeness,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Splash {
+    pub radius: f32,
+    pub damage: f32,
+    pub kind: DamageKind,
+    pub falloff: Falloff,
+}
+
+pub struct Detonation {
+    pub position: Vec3,
+    pub splash: Splash,
+    pub source: Option<Entity>,
+}
+
+pub(super) fn detonate(
+    mut commands: Commands,
+    mut detonations: EventReader<Detonation>,
+    mut damage_events: EventWriter<DamageEvent>,
+    index: Res<SpatialIndex>,
+    damageables: Query<(), With<Damageable>>,
+    mut meshes: Option<ResMut<Assets<Mesh>>>,
+    mut materials: Option<ResMut<Assets<ColorMaterial>>>,
+) {
+    for detonation in detonations.iter() {
+        let center = detonation.position.truncate();
+        let splash = detonation.splash;
+
+        for entry in index.query_radius(center, splash.radius) {
+            if !damageables.contains(entry.entity) {
+                continue;
+            }
+
+            let distance = entry.position.distance(center);
+
+            damage_events.send(DamageEvent {
+                source: detonation.source,
+                target: entry.entity,
+                amount: splash.damage * splash.falloff.factor(distance, splash.radius),
+                kind: splash.kind,
+                crit: None,
+            });
+        }
+
+        // the visuals need the render assets, which headless runs don't have
+        if let (Some(meshes), Some(materials)) = (meshes.as_mut(), materials.as_mut()) {
+            commands.spawn((
+                Name::new("Explosion"),
+                MaterialMesh2dBundle {
+                    mesh: meshes.add(shape::Circle::new(splash.radius).into()).into(),
+                    material: materials.add(ColorMaterial::from(Color::rgba(0.2, 0.4, 1., 0.5))),
+                    transform: Transform::from_translation(detonation.position),
+                    ..default()
+                },
+                Lifetime {
+                    timer: Timer::new(Duration::from_millis(300), TimerMode::Once),
+                },
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f32 = 40.;
+
+    #[test]
+    fn linear_falls_off_evenly() {
+        let falloff = Falloff::Linear;
+
+        assert_eq!(falloff.factor(0., RADIUS), 1.);
+        assert_eq!(falloff.factor(RADIUS / 2., RADIUS), 0.5);
+        assert_eq!(falloff.factor(RADIUS, RADIUS), 0.);
+        assert_eq!(falloff.factor(RADIUS * 2., RADIUS), 0.);
+    }
+
+    #[test]
+    fn quadratic_falls_off_faster() {
+        let falloff = Falloff::Quadratic;
+
+        assert_eq!(falloff.factor(0., RADIUS), 1.);
+        assert_eq!(falloff.factor(RADIUS / 2., RADIUS), 0.25);
+        assert_eq!(falloff.factor(RADIUS, RADIUS), 0.);
+        assert_eq!(falloff.factor(RADIUS * 2., RADIUS), 0.);
+    }
+
+    #[test]
+    fn none_deals_full_damage_everywhere() {
+        for distance in [0., RADIUS / 2., RADIUS] {
+            assert_eq!(Falloff::None.factor(distance, RADIUS), 1.);
+        }
+    }
+
+    #[test]
+    fn zero_radius_is_not_nan() {
+        for falloff in [Falloff::None, Falloff::Linear, Falloff::Quadratic] {
+            assert_eq!(falloff.factor(0., 0.), 1.);
+        }
+    }
+}
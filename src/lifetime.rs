@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::{clock::GameClock, simulation::SimulationApp};
+
 #[derive(Reflect, Component)]
 pub struct Lifetime {
     pub timer: Timer,
@@ -7,11 +9,11 @@ pub struct Lifetime {
 
 fn despawn_lifetime_entities(
     mut lifetimes: Query<(Entity, &mut Lifetime)>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
     mut commands: Commands,
 ) {
     for (entity, mut lifetime) in lifetimes.iter_mut() {
-        lifetime.timer.tick(time.delta());
+        lifetime.timer.tick(clock.delta());
 
         if lifetime.timer.finished() {
             commands.entity(entity).despawn();
@@ -23,7 +25,7 @@ pub struct LifetimePlugin;
 
 impl Plugin for LifetimePlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(despawn_lifetime_entities)
+        app.add_simulation_system_set(SystemSet::new().with_system(despawn_lifetime_entities))
             .register_type::<Lifetime>();
     }
 }
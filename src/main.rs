@@ -1,18 +1,74 @@
+use std::{env, process};
+
 use bevy::prelude::*;
 use tower_defense::{
-    shooting::ShootingPlugin, constantly_spawn_enemies, cursor::CursorPlugin, grid::GridPlugin,
-    health::HealthPlugin, lifetime::LifetimePlugin, setup_camera, setup_entities, setup_tower,
-    SpawnTimer,
+    boss::BossPlugin,
+    cli::{Command, Options, USAGE},
+    cursor::CursorPlugin,
+    damage_numbers::DamageNumbersPlugin,
+    enemy::EnemyPlugin,
+    grid::GridPlugin,
+    headless::{self, Scenario},
+    health::HealthPlugin,
+    lifetime::LifetimePlugin,
+    score::ScorePlugin,
+    screens::{ScreensPlugin, SkipMenu},
+    setup_camera, setup_entities,
+    shooting::ShootingPlugin,
+    simulation::SimulationPlugin,
+    spatial::SpatialPlugin,
+    state::StatePlugin,
+    status::StatusPlugin,
+    tower::TowerPlugin,
+    wave::WavePlugin,
 };
 
+fn run_headless(scenario_path: &str, seed: Option<u64>) -> Result<String, String> {
+    let mut scenario = Scenario::load(scenario_path)?;
+    if let Some(seed) = seed {
+        scenario.seed = seed;
+    }
+
+    let report = headless::run(&scenario)?;
+    serde_json::to_string_pretty(&report)
+        .map_err(|err| format!("could not write the report: {}", err))
+}
+
 fn main() {
-    App::new()
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match &options.command {
+        Command::Play => {}
+        Command::Help => {
+            println!("{}", USAGE);
+            return;
+        }
+        Command::Headless(scenario_path) => {
+            match run_headless(scenario_path, options.seed) {
+                Ok(report) => println!("{}", report),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    process::exit(1);
+                }
+            }
+            return;
+        }
+    }
+
+    let mut app = App::new();
+    app
         // external plugins
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             window: WindowDescriptor {
                 resizable: false,
-                width: 1280.,
-                height: 720.,
+                width: options.width,
+                height: options.height,
                 title: String::from("Tower Defense Game"),
                 ..default()
             },
@@ -20,23 +76,44 @@ fn main() {
         }))
         // .add_plugin(WorldInspectorPlugin)
         // game plugins
+        // the simulation stage has to exist before plugins add their systems to it
+        .add_plugin(SimulationPlugin {
+            steps_per_second: 60,
+            seed: options.seed.unwrap_or_default(),
+            lockstep: false,
+        })
+        .add_plugin(StatePlugin)
+        .add_plugin(ScreensPlugin)
         .add_plugin(CursorPlugin)
         .add_plugin(ShootingPlugin)
         .add_plugin(LifetimePlugin)
         .add_plugin(GridPlugin {
-            debug: false,
-            cell_size: 20.,
-            map_file_path: "assets/full_size.map",
+            debug: options.debug_grid,
+            cell_size: options.cell_size,
+            map_file_path: options.map_file_path,
+        })
+        .add_plugin(HealthPlugin {
+            debug: options.debug_health,
+            health_bars: true,
         })
-        .add_plugin(HealthPlugin { debug: false })
+        .add_plugin(SpatialPlugin { cell_size: 50. })
+        .add_plugin(StatusPlugin)
+        .add_plugin(EnemyPlugin)
+        .add_plugin(BossPlugin)
+        .add_plugin(DamageNumbersPlugin)
+        .add_plugin(TowerPlugin)
+        .add_plugin(WavePlugin)
+        .add_plugin(ScorePlugin)
         // other
-        .insert_resource(SpawnTimer(Timer::from_seconds(0.5, TimerMode::Repeating)))
         .add_startup_system_set(
             SystemSet::new()
                 .with_system(setup_camera)
-                .with_system(setup_entities)
-                .with_system(setup_tower),
-        )
-        .add_system(constantly_spawn_enemies)
-        .run();
+                .with_system(setup_entities),
+        );
+
+    if options.skip_menu {
+        app.insert_resource(SkipMenu);
+    }
+
+    app.run();
 }
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::{
+    enemy::Enemy,
+    health::{DamageTaken, Death, HealthSystem},
+    simulation::SimulationApp,
+    state::GameState,
+};
+
+// how the current level is going, reset whenever one starts
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct Score {
+    pub kills: u32,
+    pub leaks: u32,
+    pub gold: u32, // earned from bounties
+}
+
+// health taken off enemies by everything this tower fired, shields and status effects included
+#[derive(Component, Default)]
+pub struct DamageDealt(pub f32);
+
+fn reset_score(mut commands: Commands) {
+    commands.insert_resource(Score::default());
+}
+
+fn count_kills(mut score: ResMut<Score>, mut deaths: EventReader<Death>, enemies: Query<&Enemy>) {
+    for death in deaths.iter() {
+        if let Ok(enemy) = enemies.get(death.entity) {
+            score.kills += 1;
+            score.gold += enemy.kind.archetype().bounty;
+        }
+    }
+}
+
+fn credit_damage(mut damage_taken: EventReader<DamageTaken>, mut dealers: Query<&mut DamageDealt>) {
+    for event in damage_taken.iter() {
+        let source = match event.source {
+            Some(source) => source,
+            None => continue,
+        };
+
+        if let Ok(mut dealt) = dealers.get_mut(source) {
+            dealt.0 += event.amount + event.absorbed;
+        }
+    }
+}
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(reset_score))
+            .add_simulation_system_set(
+                SystemSet::new()
+                    .with_system(
+                        count_kills
+                            .after(HealthSystem::Apply)
+                            .before(HealthSystem::Death),
+                    )
+                    .with_system(credit_damage.after(HealthSystem::Modify)),
+            );
+    }
+}
@@ -0,0 +1,321 @@
+use bevy::prelude::*;
+
+use crate::{
+    clock::{GameClock, GameSpeed},
+    grid::GridSettings,
+    score::Score,
+    state::{GameState, Lives},
+    FONT_PATH,
+};
+
+pub struct Level {
+    pub name: &'static str,
+    pub map_file_path: &'static str,
+    pub cell_size: f32,
+}
+
+// the tower layout is made for the full size map, smaller maps need their own first
+pub const LEVELS: [Level; 1] = [Level {
+    name: "Open Field",
+    map_file_path: "assets/full_size.map",
+    cell_size: 20.,
+}];
+
+// the level was picked on the command line, the first main menu goes straight to it
+#[derive(Resource)]
+pub struct SkipMenu;
+
+const SPEED_KEYS: [(KeyCode, GameSpeed); 4] = [
+    (KeyCode::Key0, GameSpeed::Stopped),
+    (KeyCode::Key1, GameSpeed::Normal),
+    (KeyCode::Key2, GameSpeed::Double),
+    (KeyCode::Key4, GameSpeed::Quadruple),
+];
+
+const LEVEL_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+// root of whatever UI a state shows, despawned when the state is left
+#[derive(Component)]
+struct Screen;
+
+#[derive(Component)]
+struct Hud;
+
+#[derive(Component)]
+struct LivesText;
+
+fn spawn_screen(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    background: Color,
+    lines: &[(String, f32)],
+) {
+    let font = asset_server.load(FONT_PATH);
+
+    commands
+        .spawn((
+            Name::new("Screen"),
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: background.into(),
+                ..default()
+            },
+            Screen,
+        ))
+        .with_children(|parent| {
+            for (line, font_size) in lines {
+                parent.spawn(
+                    TextBundle::from_section(
+                        line.clone(),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: *font_size,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(8.)),
+                        ..default()
+                    }),
+                );
+            }
+        });
+}
+
+fn despawn_screen(mut commands: Commands, screens: Query<Entity, With<Screen>>) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        Color::BLACK,
+        &[
+            ("Tower Defense".to_string(), 64.),
+            ("Press Enter to play".to_string(), 24.),
+        ],
+    );
+}
+
+fn setup_level_select(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let mut lines = vec![("Select a level".to_string(), 48.)];
+    lines.extend(
+        LEVELS
+            .iter()
+            .enumerate()
+            .map(|(i, level)| (format!("{} - {}", i + 1, level.name), 24.)),
+    );
+    lines.push(("Esc - back".to_string(), 18.));
+
+    spawn_screen(&mut commands, &asset_server, Color::BLACK, &lines);
+}
+
+fn setup_paused(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        Color::rgba(0., 0., 0., 0.6),
+        &[
+            ("Paused".to_string(), 48.),
+            ("Esc - resume".to_string(), 24.),
+            ("0 / 1 / 2 / 4 - game speed while playing".to_string(), 18.),
+            ("Q - quit to the main menu".to_string(), 24.),
+        ],
+    );
+}
+
+fn setup_victory(mut commands: Commands, asset_server: Res<AssetServer>, lives: Res<Lives>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        Color::BLACK,
+        &[
+            ("Victory!".to_string(), 64.),
+            (format!("{} lives left", lives.0), 24.),
+            ("Press Enter to continue".to_string(), 24.),
+        ],
+    );
+}
+
+fn setup_game_over(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        Color::BLACK,
+        &[
+            ("Game Over".to_string(), 64.),
+            ("Press Enter to continue".to_string(), 24.),
+        ],
+    );
+}
+
+fn setup_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Name::new("Lives"),
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load(FONT_PATH),
+                font_size: 20.,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(10.),
+                left: Val::Px(10.),
+                ..default()
+            },
+            ..default()
+        }),
+        Hud,
+        LivesText,
+    ));
+}
+
+fn update_hud(
+    lives: Res<Lives>,
+    score: Res<Score>,
+    clock: Res<GameClock>,
+    mut texts: Query<&mut Text, With<LivesText>>,
+) {
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!(
+            "Lives: {}   Gold: {}   Speed: {}x",
+            lives.0,
+            score.gold,
+            clock.speed().multiplier()
+        );
+    }
+}
+
+fn despawn_hud(mut commands: Commands, hud: Query<Entity, With<Hud>>) {
+    for entity in hud.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// inputs are cleared once handled, so the next screen doesn't react to the same key press
+fn main_menu_input(mut keys: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if keys.clear_just_pressed(KeyCode::Return) {
+        let _ = state.set(GameState::LevelSelect);
+    }
+}
+
+fn skip_main_menu(
+    mut commands: Commands,
+    skip: Option<Res<SkipMenu>>,
+    mut state: ResMut<State<GameState>>,
+) {
+    if skip.is_none() {
+        return;
+    }
+
+    commands.remove_resource::<SkipMenu>();
+    let _ = state.set(GameState::Playing);
+}
+
+fn level_select_input(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut state: ResMut<State<GameState>>,
+    mut settings: ResMut<GridSettings>,
+) {
+    if keys.clear_just_pressed(KeyCode::Escape) {
+        let _ = state.set(GameState::MainMenu);
+        return;
+    }
+
+    for (level, key) in LEVELS.iter().zip(LEVEL_KEYS) {
+        if keys.clear_just_pressed(key) {
+            settings.map_file_path = level.map_file_path.to_string();
+            settings.cell_size = level.cell_size;
+            let _ = state.set(GameState::Playing);
+            return;
+        }
+    }
+}
+
+fn playing_input(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut state: ResMut<State<GameState>>,
+    mut clock: ResMut<GameClock>,
+) {
+    if keys.clear_just_pressed(KeyCode::Escape) {
+        let _ = state.push(GameState::Paused);
+    }
+
+    for (key, speed) in SPEED_KEYS {
+        if keys.clear_just_pressed(key) {
+            clock.set_speed(speed);
+        }
+    }
+}
+
+fn paused_input(mut keys: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if keys.clear_just_pressed(KeyCode::Escape) {
+        let _ = state.pop();
+    } else if keys.clear_just_pressed(KeyCode::Q) {
+        // leaves the level underneath as well, which cleans it up
+        let _ = state.replace(GameState::MainMenu);
+    }
+}
+
+fn end_screen_input(mut keys: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if keys.clear_just_pressed(KeyCode::Return) {
+        let _ = state.set(GameState::MainMenu);
+    }
+}
+
+pub struct ScreensPlugin;
+
+impl Plugin for ScreensPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(GameState::MainMenu).with_system(setup_main_menu))
+            .add_system_set(SystemSet::on_update(GameState::MainMenu).with_system(main_menu_input))
+            .add_system_set(SystemSet::on_update(GameState::MainMenu).with_system(skip_main_menu))
+            .add_system_set(SystemSet::on_exit(GameState::MainMenu).with_system(despawn_screen))
+            .add_system_set(
+                SystemSet::on_enter(GameState::LevelSelect).with_system(setup_level_select),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::LevelSelect).with_system(level_select_input),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::LevelSelect).with_system(despawn_screen))
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(setup_hud))
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(playing_input)
+                    .with_system(update_hud),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(despawn_hud))
+            .add_system_set(SystemSet::on_enter(GameState::Paused).with_system(setup_paused))
+            .add_system_set(SystemSet::on_update(GameState::Paused).with_system(paused_input))
+            .add_system_set(SystemSet::on_exit(GameState::Paused).with_system(despawn_screen))
+            .add_system_set(SystemSet::on_enter(GameState::Victory).with_system(setup_victory))
+            .add_system_set(SystemSet::on_update(GameState::Victory).with_system(end_screen_input))
+            .add_system_set(SystemSet::on_exit(GameState::Victory).with_system(despawn_screen))
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(setup_game_over))
+            .add_system_set(SystemSet::on_update(GameState::GameOver).with_system(end_screen_input))
+            .add_system_set(SystemSet::on_exit(GameState::GameOver).with_system(despawn_screen));
+    }
+}
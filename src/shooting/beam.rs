@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{
+    clock::GameClock,
+    health::{damage::DamageKind, DamageEvent},
+};
+
+use super::{
+    line_transform,
+    weapon::{ShotFired, Weapon},
+    Shooter, Targetable,
+};
+
+const BEAM_TICK: Duration = Duration::from_millis(100);
+const BEAM_WIDTH: f32 = 3.;
+
+#[derive(Clone, Copy)]
+pub struct Ramp {
+    pub per_second: f32, // how much the damage multiplier grows for every second locked on
+    pub max_multiplier: f32,
+}
+
+#[derive(Component)]
+pub struct BeamShooter {
+    pub damage_per_second: f32,
+    pub kind: DamageKind,
+    pub ramp: Option<Ramp>,
+}
+
+#[derive(Component)]
+pub struct Beam {
+    shooter: Entity,
+    target: Entity,
+    locked_for: Duration,
+    tick: Timer,
+}
+
+impl Beam {
+    fn damage_multiplier(&self, ramp: Option<Ramp>) -> f32 {
+        ramp.map_or(1., |ramp| {
+            (1. + ramp.per_second * self.locked_for.as_secs_f32()).min(ramp.max_multiplier)
+        })
+    }
+}
+
+pub fn update_beams(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut beams: Query<(Entity, &mut Beam, &mut Transform)>,
+    shooters: Query<(&Transform, &Shooter, &Weapon, &BeamShooter), Without<Beam>>,
+    targetables: Query<&Transform, (With<Targetable>, Without<Beam>)>,
+) {
+    for (beam_entity, mut beam, mut transform) in beams.iter_mut() {
+        let shooter = shooters.get(beam.shooter).ok();
+        let target_transform = targetables.get(beam.target).ok();
+
+        // stop once the tower switched targets, the target died or it left the range
+        let locked = match (shooter, target_transform) {
+            (Some((shooter_transform, shooter, weapon, beam_shooter)), Some(target_transform)) => {
+                let shooter_pos = shooter_transform.translation.truncate();
+
+                (shooter.target == Some(beam.target)
+                    && shooter.in_range(&shooter_pos, &target_transform.translation))
+                .then_some((shooter_transform, weapon, beam_shooter, target_transform))
+            }
+            _ => None,
+        };
+
+        if let Some((shooter_transform, weapon, beam_shooter, target_transform)) = locked {
+            beam.locked_for += clock.delta();
+            *transform = line_transform(
+                shooter_transform.translation,
+                target_transform.translation,
+                BEAM_WIDTH,
+            );
+
+            let ticks = beam.tick.tick(clock.delta()).times_finished_this_tick();
+            if ticks > 0 {
+                let multiplier =
+                    beam.damage_multiplier(beam_shooter.ramp) * weapon.damage_multiplier;
+
+                damage_events.send(DamageEvent {
+                    source: Some(beam.shooter),
+                    target: beam.target,
+                    amount: beam_shooter.damage_per_second
+                        * BEAM_TICK.as_secs_f32()
+                        * ticks as f32
+                        * multiplier,
+                    kind: beam_shooter.kind,
+                    crit: None,
+                });
+            }
+        } else {
+            commands.entity(beam_entity).despawn();
+        }
+    }
+}
+
+pub fn fire_beams(
+    mut commands: Commands,
+    mut shots: EventWriter<ShotFired>,
+    mut shooters: Query<(Entity, &Transform, &Shooter, &mut Weapon), With<BeamShooter>>,
+    beams: Query<&Beam>,
+    targetables: Query<&Transform, With<Targetable>>,
+) {
+    let firing: HashSet<Entity> = beams.iter().map(|beam| beam.shooter).collect();
+
+    for (entity, transform, shooter, mut weapon) in shooters.iter_mut() {
+        if firing.contains(&entity) || !weapon.is_ready() {
+            continue;
+        }
+
+        let target = shooter
+            .target
+            .and_then(|target| targetables.get(target).ok().map(|t| (target, t)));
+
+        if let Some((target, target_transform)) = target {
+            let shooter_pos = transform.translation.truncate();
+
+            if shooter.in_range(&shooter_pos, &target_transform.translation) {
+                commands.spawn((
+                    Name::new("Beam"),
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgba(1., 0.2, 0.8, 0.8),
+                            ..default()
+                        },
+                        transform: line_transform(
+                            transform.translation,
+                            target_transform.translation,
+                            BEAM_WIDTH,
+                        ),
+                        ..default()
+                    },
+                    Beam {
+                        shooter: entity,
+                        target,
+                        locked_for: Duration::ZERO,
+                        tick: Timer::new(BEAM_TICK, TimerMode::Repeating),
+                    },
+                ));
+                weapon.fire();
+                shots.send(ShotFired {
+                    shooter: entity,
+                    target,
+                    origin: transform.translation,
+                });
+            }
+        }
+    }
+}
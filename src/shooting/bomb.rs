@@ -1,20 +1,49 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
-use bevy::sprite::collide_aabb::collide;
 
 use crate::{
-    health::{DamageDealer, Damageable},
+    grid::{agent::GridAgent, DebugGrid},
+    health::{
+        damage::DamageKind,
+        splash::{Detonation, Splash},
+        DamageDealer, Damageable, HitMode,
+    },
     lifetime::Lifetime,
+    spatial::SpatialIndex,
 };
 
-use super::{Shooter, Shootable};
+use super::{
+    weapon::{ShotFired, Weapon},
+    Shootable, Shooter, Targetable,
+};
+
+const BOMB_SPEED: f32 = 300.;
+
+// a burning patch left behind where the bomb went off
+#[derive(Clone, Copy)]
+pub struct Lingering {
+    pub size: f32,
+    pub damage: f32, // per hit, every interval
+    pub kind: DamageKind,
+    pub interval: Duration,
+    pub duration: Duration,
+}
 
 #[derive(Component)]
-pub struct Bomb;
+pub struct Bomb {
+    pub source: Option<Entity>,
+    pub splash: Splash,
+    pub lingering: Option<Lingering>,
+    pub ground_target: Option<Vec3>, // detonate here instead of on contact with a damageable
+}
 
 #[derive(Component)]
-pub struct BombShooter;
+pub struct BombShooter {
+    pub splash: Splash,
+    pub lingering: Option<Lingering>,
+    pub ground_impact: bool,
+}
 
 #[derive(Bundle)]
 pub struct BombBundle {
@@ -27,12 +56,20 @@ pub struct BombBundle {
 }
 
 impl BombBundle {
-    pub fn new(position: Vec3, target: &Vec3, speed: f32) -> Self {
+    pub fn new(
+        source: Entity,
+        position: Vec3,
+        target: &Vec3,
+        speed: f32,
+        splash: Splash,
+        lingering: Option<Lingering>,
+        ground_impact: bool,
+    ) -> Self {
         let trajectory =
             Vec2::new(target.x - position.x, target.y - position.y).normalize() * speed;
 
         BombBundle {
-            name: Name::new("Bullet"),
+            name: Name::new("Bomb"),
             shootable: Shootable { trajectory },
             lifetime: Lifetime {
                 timer: Timer::new(Duration::from_millis(5000), TimerMode::Once),
@@ -46,85 +83,141 @@ impl BombBundle {
                     .with_scale(Vec3::new(10., 10., 1.)),
                 ..default()
             },
-            bomb: Bomb,
+            bomb: Bomb {
+                source: Some(source),
+                splash,
+                lingering,
+                ground_target: ground_impact.then_some(*target),
+            },
         }
     }
 }
 
 pub fn shoot_bomb(
     mut commands: Commands,
-    time: Res<Time>,
-    mut shooters: Query<(&Transform, &mut Shooter, &BombShooter)>,
+    mut shots: EventWriter<ShotFired>,
+    mut shooters: Query<(Entity, &Transform, &Shooter, &mut Weapon, &BombShooter)>,
+    targetables: Query<(&Transform, Option<&GridAgent>), With<Targetable>>,
+    grid: Query<&DebugGrid>,
 ) {
-    for (transform, mut shooter, _) in shooters.iter_mut() {
-        shooter.cooldown.tick(time.delta());
-
-        if let Some(target) = shooter.target {
-            if shooter.cooldown.finished() {
-                let distance = transform.translation.distance(target);
-
-                if distance < shooter.range {
-                    commands.spawn(BombBundle::new(
-                        transform.translation.clone(),
-                        &target,
-                        300.,
-                    ));
-                }
+    let grid = grid.single();
+
+    for (entity, transform, shooter, mut weapon, bomb_shooter) in shooters.iter_mut() {
+        if !weapon.is_ready() {
+            continue;
+        }
+
+        let target = shooter
+            .target
+            .and_then(|target| targetables.get(target).ok().map(|t| (target, t)));
+
+        if let Some((target, (target_transform, target_agent))) = target {
+            let shooter_pos = transform.translation.truncate();
+
+            if shooter.in_range(&shooter_pos, &target_transform.translation) {
+                let target_velocity = target_agent.map_or(Vec2::ZERO, |agent| {
+                    agent.velocity(grid, &target_transform.translation)
+                });
+                let aim = shooter.aim_point(
+                    &transform.translation,
+                    &target_transform.translation,
+                    target_velocity,
+                    BOMB_SPEED,
+                );
+
+                commands.spawn(BombBundle::new(
+                    entity,
+                    transform.translation,
+                    &aim,
+                    BOMB_SPEED,
+                    Splash {
+                        damage: bomb_shooter.splash.damage * weapon.damage_multiplier,
+                        ..bomb_shooter.splash
+                    },
+                    bomb_shooter.lingering,
+                    bomb_shooter.ground_impact,
+                ));
+                weapon.fire();
+                shots.send(ShotFired {
+                    shooter: entity,
+                    target,
+                    origin: transform.translation,
+                });
             }
         }
     }
 }
 
-// spawn an explosion when colliding with a damageable
+fn spawn_zone(
+    commands: &mut Commands,
+    position: Vec3,
+    lingering: Lingering,
+    source: Option<Entity>,
+) {
+    let mut damage_dealer =
+        DamageDealer::new(lingering.damage, lingering.kind).with_hit_mode(HitMode::Zone {
+            interval: lingering.interval,
+        });
+    if let Some(source) = source {
+        damage_dealer = damage_dealer.with_source(source);
+    }
+
+    let size = Vec3::new(lingering.size, lingering.size, 1.);
+    commands.spawn((
+        Name::new("Burning Ground"),
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1., 0.4, 0., 0.3),
+                ..default()
+            },
+            // the scale doubles as the area the zone hits
+            transform: Transform::from_xyz(position.x, position.y, 0.5).with_scale(size),
+            ..default()
+        },
+        damage_dealer,
+        Lifetime {
+            timer: Timer::new(lingering.duration, TimerMode::Once),
+        },
+    ));
+}
+
+// detonate exactly once, either on contact with a damageable or when reaching the ground target
 pub fn explode_bomb(
     mut commands: Commands,
-    mut bombs: Query<(Entity, &Transform, &mut Bomb)>,
-    mut damageables: Query<(&Transform, &mut Damageable)>,
+    index: Res<SpatialIndex>,
+    mut detonations: EventWriter<Detonation>,
+    bombs: Query<(Entity, &Transform, &Shootable, &Bomb)>,
+    damageables: Query<(), With<Damageable>>,
 ) {
-    for (bomb_entity, bomb_transform, _) in bombs.iter_mut() {
-        let bomb_size = Vec2::new(bomb_transform.scale.x, bomb_transform.scale.y);
-
-        for (damageable_transform, _) in damageables.iter_mut() {
-            let damageable_size =
-                Vec2::new(damageable_transform.scale.x, damageable_transform.scale.y);
-
-            if (collide(
-                bomb_transform.translation,
-                bomb_size,
-                damageable_transform.translation,
-                damageable_size,
-            ))
-            .is_some()
-            {
-                let explosion_transform = Transform {
-                    translation: damageable_transform.translation,
-                    scale: Vec3::new(100., 100., 1.),
-                    ..default()
-                };
-
-                commands.entity(bomb_entity).despawn();
-                commands.spawn((
-                    SpriteBundle {
-                        sprite: Sprite {
-                            color: Color::BLUE,
-                            ..default()
-                        },
-                        transform: explosion_transform.clone(),
-                        ..default()
-                    },
-                    Lifetime {
-                        timer: Timer::new(Duration::from_millis(500), TimerMode::Once),
-                    },
-                ));
+    for (bomb_entity, bomb_transform, shootable, bomb) in bombs.iter() {
+        let bomb_pos = bomb_transform.translation.truncate();
+
+        let impact_point = match bomb.ground_target {
+            // reached once the target is no longer ahead of the bomb
+            Some(ground_target) => {
+                let ahead = (ground_target.truncate() - bomb_pos).dot(shootable.trajectory) > 0.;
+                (!ahead).then_some(ground_target)
+            }
+            None => {
+                let bomb_size = bomb_transform.scale.truncate();
+                let hit = index
+                    .query_aabb(bomb_pos, bomb_size)
+                    .any(|entry| damageables.contains(entry.entity));
 
-                commands.spawn((
-                    TransformBundle {
-                        local: explosion_transform.clone(),
-                        ..default()
-                    },
-                    DamageDealer { damage: 50. },
-                ));
+                hit.then_some(bomb_transform.translation)
+            }
+        };
+
+        if let Some(position) = impact_point {
+            commands.entity(bomb_entity).despawn();
+            if let Some(lingering) = bomb.lingering {
+                spawn_zone(&mut commands, position, lingering, bomb.source);
             }
+            detonations.send(Detonation {
+                position,
+                splash: bomb.splash,
+                source: bomb.source,
+            });
         }
     }
 }
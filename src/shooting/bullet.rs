@@ -2,15 +2,28 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 
-use crate::{health::DamageDealer, lifetime::Lifetime};
+use crate::{
+    grid::{agent::GridAgent, DebugGrid},
+    health::{damage::DamageKind, DamageDealer},
+    lifetime::Lifetime,
+    status::StatusPayload,
+};
 
-use super::{Shooter, Shootable};
+use super::{
+    weapon::{ShotFired, Weapon},
+    Shootable, Shooter, Targetable,
+};
+
+const BULLET_SPEED: f32 = 300.;
 
 #[derive(Reflect, Component)]
 pub struct Bullet;
 
 #[derive(Reflect, Component)]
-pub struct BulletShooter;
+pub struct BulletShooter {
+    pub damage: f32,
+    pub kind: DamageKind,
+}
 
 #[derive(Bundle)]
 pub struct BulletBundle {
@@ -24,7 +37,14 @@ pub struct BulletBundle {
 }
 
 impl BulletBundle {
-    pub fn new(position: Vec3, target: &Vec3, speed: f32) -> Self {
+    pub fn new(
+        source: Entity,
+        position: Vec3,
+        target: &Vec3,
+        speed: f32,
+        damage: f32,
+        kind: DamageKind,
+    ) -> Self {
         let trajectory =
             Vec2::new(target.x - position.x, target.y - position.y).normalize() * speed;
 
@@ -43,7 +63,7 @@ impl BulletBundle {
                     .with_scale(Vec3::new(10., 10., 1.)),
                 ..default()
             },
-            damage_dealer: DamageDealer { damage: 10. },
+            damage_dealer: DamageDealer::new(damage, kind).with_source(source),
             bullet: Bullet,
         }
     }
@@ -51,23 +71,57 @@ impl BulletBundle {
 
 pub fn shoot_bullet(
     mut commands: Commands,
-    time: Res<Time>,
-    mut shooters: Query<(&Transform, &mut Shooter, &BulletShooter)>,
+    mut shots: EventWriter<ShotFired>,
+    mut shooters: Query<(Entity, &Transform, &Shooter, &mut Weapon, &BulletShooter)>,
+    payloads: Query<&StatusPayload>,
+    targetables: Query<(&Transform, Option<&GridAgent>), With<Targetable>>,
+    grid: Query<&DebugGrid>,
 ) {
-    for (transform, mut shooter, _) in shooters.iter_mut() {
-        shooter.cooldown.tick(time.delta());
-
-        if let Some(target) = shooter.target {
-            if shooter.cooldown.finished() {
-                let distance = transform.translation.distance(target);
-
-                if distance < shooter.range {
-                    commands.spawn(BulletBundle::new(
-                        transform.translation.clone(),
-                        &target,
-                        300.,
-                    ));
+    let grid = grid.single();
+
+    for (entity, transform, shooter, mut weapon, bullet_shooter) in shooters.iter_mut() {
+        if !weapon.is_ready() {
+            continue;
+        }
+
+        let target = shooter
+            .target
+            .and_then(|target| targetables.get(target).ok().map(|t| (target, t)));
+
+        if let Some((target, (target_transform, target_agent))) = target {
+            let shooter_pos = transform.translation.truncate();
+
+            if shooter.in_range(&shooter_pos, &target_transform.translation) {
+                let target_velocity = target_agent.map_or(Vec2::ZERO, |agent| {
+                    agent.velocity(grid, &target_transform.translation)
+                });
+                let aim = shooter.aim_point(
+                    &transform.translation,
+                    &target_transform.translation,
+                    target_velocity,
+                    BULLET_SPEED,
+                );
+
+                let mut bullet = commands.spawn(BulletBundle::new(
+                    entity,
+                    transform.translation,
+                    &aim,
+                    BULLET_SPEED,
+                    bullet_shooter.damage * weapon.damage_multiplier,
+                    bullet_shooter.kind,
+                ));
+
+                // the tower's status effects ride along with every bullet
+                if let Ok(payload) = payloads.get(entity) {
+                    bullet.insert(payload.clone());
                 }
+
+                weapon.fire();
+                shots.send(ShotFired {
+                    shooter: entity,
+                    target,
+                    origin: transform.translation,
+                });
             }
         }
     }
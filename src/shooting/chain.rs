@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    health::{damage::DamageKind, DamageEvent},
+    lifetime::Lifetime,
+    spatial::SpatialIndex,
+};
+
+use super::{
+    line_transform,
+    weapon::{ShotFired, Weapon},
+    Detection, Shooter, Targetable,
+};
+
+const ARC_WIDTH: f32 = 2.;
+
+#[derive(Component)]
+pub struct ChainShooter {
+    pub damage: f32,
+    pub kind: DamageKind,
+    pub jumps: u32,       // enemies hit after the primary target
+    pub jump_radius: f32, // how far away the next enemy can be
+    pub falloff: f32,     // damage multiplier applied on every jump
+}
+
+fn spawn_arc(commands: &mut Commands, from: Vec3, to: Vec3) {
+    commands.spawn((
+        Name::new("Arc"),
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.6, 0.8, 1.),
+                ..default()
+            },
+            transform: line_transform(from, to, ARC_WIDTH),
+            ..default()
+        },
+        Lifetime {
+            timer: Timer::new(Duration::from_millis(150), TimerMode::Once),
+        },
+    ));
+}
+
+pub fn shoot_chain(
+    mut commands: Commands,
+    mut shots: EventWriter<ShotFired>,
+    mut damage_events: EventWriter<DamageEvent>,
+    index: Res<SpatialIndex>,
+    detection: Detection,
+    mut shooters: Query<(Entity, &Transform, &Shooter, &mut Weapon, &ChainShooter)>,
+    targetables: Query<&Transform, With<Targetable>>,
+) {
+    for (entity, transform, shooter, mut weapon, chain_shooter) in shooters.iter_mut() {
+        if !weapon.is_ready() {
+            continue;
+        }
+
+        let target = shooter
+            .target
+            .and_then(|target| targetables.get(target).ok().map(|t| (target, t)));
+
+        if let Some((target, target_transform)) = target {
+            let shooter_pos = transform.translation.truncate();
+
+            if !shooter.in_range(&shooter_pos, &target_transform.translation) {
+                continue;
+            }
+
+            let mut hit = vec![target];
+            let mut current = target;
+            let mut damage = chain_shooter.damage * weapon.damage_multiplier;
+            let mut from = transform.translation;
+            let mut to = target_transform.translation;
+
+            loop {
+                damage_events.send(DamageEvent {
+                    source: Some(entity),
+                    target: current,
+                    amount: damage,
+                    kind: chain_shooter.kind,
+                    crit: None,
+                });
+                spawn_arc(&mut commands, from, to);
+
+                if hit.len() > chain_shooter.jumps as usize {
+                    break;
+                }
+
+                // nobody gets hit twice by the same shot
+                let next = index.closest(to.truncate(), chain_shooter.jump_radius, |entity| {
+                    detection.visible(entity) && !hit.contains(&entity)
+                });
+                let next = next.and_then(|next| targetables.get(next).ok().map(|t| (next, t)));
+
+                match next {
+                    Some((next, next_transform)) => {
+                        hit.push(next);
+                        current = next;
+                        damage *= chain_shooter.falloff;
+                        from = to;
+                        to = next_transform.translation;
+                    }
+                    None => break,
+                }
+            }
+
+            weapon.fire();
+            shots.send(ShotFired {
+                shooter: entity,
+                target,
+                origin: transform.translation,
+            });
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    clock::GameClock,
+    health::{
+        damage::{Crit, DamageKind},
+        DamageDealer,
+    },
+    lifetime::Lifetime,
+    spatial::SpatialIndex,
+};
+
+use super::{
+    weapon::{ShotFired, Weapon},
+    Detection, Shootable, Shooter, Targetable,
+};
+
+#[derive(Clone, Copy)]
+pub enum OnTargetLost {
+    Retarget { range: f32 },
+    Fizzle,
+}
+
+#[derive(Component)]
+pub struct Homing {
+    pub target: Option<Entity>,
+    pub turn_rate: f32, // radians per second
+    pub max_speed: f32,
+    pub on_target_lost: OnTargetLost,
+}
+
+#[derive(Component)]
+pub struct HomingShooter {
+    pub crit: Option<Crit>,
+}
+
+#[derive(Bundle)]
+pub struct HomingBundle {
+    pub name: Name,
+    pub shootable: Shootable,
+    pub homing: Homing,
+    pub lifetime: Lifetime,
+    #[bundle]
+    pub sprite: SpriteBundle,
+    pub damage_dealer: DamageDealer,
+}
+
+impl HomingBundle {
+    pub fn new(
+        source: Entity,
+        position: Vec3,
+        target: Entity,
+        target_pos: &Vec3,
+        max_speed: f32,
+        damage: f32,
+        crit: Option<Crit>,
+    ) -> Self {
+        let trajectory = (*target_pos - position).truncate().normalize_or_zero() * max_speed;
+
+        let mut damage_dealer =
+            DamageDealer::new(damage, DamageKind::Explosive).with_source(source);
+        if let Some(crit) = crit {
+            damage_dealer = damage_dealer.with_crit(crit);
+        }
+
+        HomingBundle {
+            name: Name::new("Homing Missile"),
+            shootable: Shootable { trajectory },
+            homing: Homing {
+                target: Some(target),
+                turn_rate: 4.,
+                max_speed,
+                on_target_lost: OnTargetLost::Retarget { range: 150. },
+            },
+            lifetime: Lifetime {
+                timer: Timer::new(Duration::from_millis(5000), TimerMode::Once),
+            },
+            sprite: SpriteBundle {
+                sprite: Sprite {
+                    color: Color::ORANGE,
+                    ..default()
+                },
+                transform: Transform::from_translation(position).with_scale(Vec3::new(8., 8., 1.)),
+                ..default()
+            },
+            damage_dealer,
+        }
+    }
+}
+
+pub fn shoot_homing(
+    mut commands: Commands,
+    mut shots: EventWriter<ShotFired>,
+    mut shooters: Query<(Entity, &Transform, &Shooter, &mut Weapon, &HomingShooter)>,
+    targetables: Query<&Transform, With<Targetable>>,
+) {
+    for (entity, transform, shooter, mut weapon, homing_shooter) in shooters.iter_mut() {
+        if !weapon.is_ready() {
+            continue;
+        }
+
+        let target = shooter
+            .target
+            .and_then(|target| targetables.get(target).ok().map(|t| (target, t)));
+
+        if let Some((target, target_transform)) = target {
+            let shooter_pos = transform.translation.truncate();
+
+            if shooter.in_range(&shooter_pos, &target_transform.translation) {
+                commands.spawn(HomingBundle::new(
+                    entity,
+                    transform.translation,
+                    target,
+                    &target_transform.translation,
+                    250.,
+                    15. * weapon.damage_multiplier,
+                    homing_shooter.crit,
+                ));
+                weapon.fire();
+                shots.send(ShotFired {
+                    shooter: entity,
+                    target,
+                    origin: transform.translation,
+                });
+            }
+        }
+    }
+}
+
+// turn the projectile's trajectory towards its target, limited by the turn rate
+pub fn steer_homing(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    index: Res<SpatialIndex>,
+    detection: Detection,
+    mut projectiles: Query<(Entity, &Transform, &mut Shootable, &mut Homing)>,
+    targetables: Query<(Entity, &Transform), With<Targetable>>,
+) {
+    for (entity, transform, mut shootable, mut homing) in projectiles.iter_mut() {
+        let position = transform.translation.truncate();
+
+        // a target that slipped back into stealth is lost just like a dead one
+        let locked = homing
+            .target
+            .is_some_and(|target| detection.visible(target));
+
+        if !locked {
+            homing.target = match homing.on_target_lost {
+                OnTargetLost::Retarget { range } => {
+                    index.closest(position, range, |entity| detection.visible(entity))
+                }
+                OnTargetLost::Fizzle => None,
+            };
+        }
+
+        let target_transform = homing
+            .target
+            .and_then(|target| targetables.get(target).ok());
+
+        if let Some((_, target_transform)) = target_transform {
+            let current = shootable.trajectory.normalize_or_zero();
+            let desired = (target_transform.translation.truncate() - position).normalize_or_zero();
+
+            if current == Vec2::ZERO || desired == Vec2::ZERO {
+                shootable.trajectory = desired * homing.max_speed;
+                continue;
+            }
+
+            let max_turn = homing.turn_rate * clock.delta_seconds();
+            let turn = current.angle_between(desired).clamp(-max_turn, max_turn);
+
+            shootable.trajectory = Vec2::from_angle(turn).rotate(current) * homing.max_speed;
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+}
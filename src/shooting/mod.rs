@@ -1,87 +1,332 @@
-use bevy::{prelude::*, ecs::query::QueryIter};
+use bevy::{ecs::system::SystemParam, prelude::*};
 
-use self::{bullet::shoot_bullet, bomb::{shoot_bomb, explode_bomb}};
+use crate::{
+    clock::GameClock,
+    grid::agent::follow_path,
+    health::HealthSystem,
+    simulation::{SimulationApp, SimulationStage},
+    spatial::SpatialIndex,
+};
 
-pub mod bullet;
+use self::{
+    beam::{fire_beams, update_beams},
+    bomb::{explode_bomb, shoot_bomb},
+    bullet::shoot_bullet,
+    chain::shoot_chain,
+    homing::{shoot_homing, steer_homing},
+    stats::{apply_auras, capture_base_stats, track_removed_stats, StatsDirty},
+    weapon::{tick_weapons, ShotFired},
+};
+
+pub mod beam;
 pub mod bomb;
+pub mod bullet;
+pub mod chain;
+pub mod homing;
+pub mod stats;
+pub mod weapon;
+
+#[derive(SystemLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ShootingSystem {
+    Targeting,
+    Readiness,
+    Fire,
+}
 
 #[derive(Reflect, Component)]
 pub struct Shootable {
     pub trajectory: Vec2,
 }
 
-fn update_shootable_position(time: Res<Time>, mut bullets: Query<(&mut Transform, &Shootable)>) {
+fn update_shootable_position(
+    clock: Res<GameClock>,
+    mut bullets: Query<(&mut Transform, &Shootable)>,
+) {
     for (mut transform, bullet) in bullets.iter_mut() {
         transform.translation.x =
-            transform.translation.x + bullet.trajectory.x * time.delta_seconds();
+            transform.translation.x + bullet.trajectory.x * clock.delta_seconds();
         transform.translation.y =
-            transform.translation.y + bullet.trajectory.y * time.delta_seconds();
+            transform.translation.y + bullet.trajectory.y * clock.delta_seconds();
     }
 }
 
+// a sprite with this transform is stretched into a line between the two points
+pub(crate) fn line_transform(from: Vec3, to: Vec3, width: f32) -> Transform {
+    let offset = (to - from).truncate();
+    let middle = (from + to) / 2.;
+
+    Transform::from_xyz(middle.x, middle.y, 5.)
+        .with_rotation(Quat::from_rotation_z(offset.y.atan2(offset.x)))
+        .with_scale(Vec3::new(offset.length(), width, 1.))
+}
+
 #[derive(Component)]
 pub struct Shooter {
-    pub cooldown: Timer,
     pub range: f32,
-    pub target: Option<Vec3>,
+    pub target: Option<Entity>,
+    pub lead: bool, // aim where the target will be instead of where it is
 }
 
-pub fn compute_target( mut shooters: Query<(&Transform, &mut Shooter)>,
-    targetables: Query<&Transform, With<Targetable>>,
-) {
-    for (transform, mut shooter) in shooters.iter_mut() {
-        let shooter_pos = Vec2::new(transform.translation.x, transform.translation.y);
-        let targetables_iter = targetables.iter();
+impl Shooter {
+    pub fn in_range(&self, shooter_pos: &Vec2, target_pos: &Vec3) -> bool {
+        shooter_pos.distance(target_pos.truncate()) < self.range
+    }
+
+    pub fn aim_point(
+        &self,
+        shooter_pos: &Vec3,
+        target_pos: &Vec3,
+        target_velocity: Vec2,
+        projectile_speed: f32,
+    ) -> Vec3 {
+        if !self.lead {
+            return *target_pos;
+        }
 
-        shooter.target = get_shortest_distance_for(&shooter_pos, targetables_iter);
+        intercept_point(
+            shooter_pos.truncate(),
+            target_pos.truncate(),
+            target_velocity,
+            projectile_speed,
+        )
+        .map_or(*target_pos, |point| point.extend(target_pos.z))
     }
 }
 
-fn get_shortest_distance_for(
-    pos: &Vec2,
-    mut targetables_iter: QueryIter<&Transform, With<Targetable>>,
-) -> Option<Vec3> {
-    let mut shortest: Option<Vec3> = None;
-
-    if let Some(first) = targetables_iter.next() {
-        shortest = Some(first.translation);
+// point where a projectile fired now at the given speed meets a target moving with
+// constant velocity, none if the projectile can never catch up
+pub fn intercept_point(
+    shooter_pos: Vec2,
+    target_pos: Vec2,
+    target_velocity: Vec2,
+    projectile_speed: f32,
+) -> Option<Vec2> {
+    // a standing target is hit where it stands
+    if target_velocity == Vec2::ZERO {
+        return Some(target_pos);
     }
 
-    for targetable_transform in targetables_iter {
-        let targetable_pos = Vec2::new(
-            targetable_transform.translation.x,
-            targetable_transform.translation.y,
-        );
+    // solve |offset + target_velocity * t| = projectile_speed * t for the smallest t > 0
+    let offset = target_pos - shooter_pos;
+    let a = target_velocity.length_squared() - projectile_speed * projectile_speed;
+    let b = 2. * offset.dot(target_velocity);
+    let c = offset.length_squared();
+
+    let time = if a.abs() < f32::EPSILON {
+        if b >= 0. {
+            return None;
+        }
+
+        -c / b
+    } else {
+        let discriminant = b * b - 4. * a * c;
+        if discriminant < 0. {
+            return None;
+        }
 
-        if let Some(current_target) = shortest {
-            let current_target = Vec2::new(current_target.x, current_target.y);
+        let root = discriminant.sqrt();
+        let t1 = (-b - root) / (2. * a);
+        let t2 = (-b + root) / (2. * a);
+
+        match (t1 > 0., t2 > 0.) {
+            (true, true) => t1.min(t2),
+            (true, false) => t1,
+            (false, true) => t2,
+            (false, false) => return None,
+        }
+    };
+
+    Some(target_pos + target_velocity * time)
+}
+
+// stealthy targetables can only be aimed at, homed in on or jumped to while a detector covers them
+#[derive(SystemParam)]
+pub struct Detection<'w, 's> {
+    targetables: Query<'w, 's, (&'static Transform, &'static Targetable)>,
+    detectors: Query<'w, 's, (&'static Transform, &'static Detector)>,
+}
 
-            let shooter_to_current = pos.distance(current_target);
-            let shooter_to_targetable = pos.distance(targetable_pos);
+impl<'w, 's> Detection<'w, 's> {
+    pub fn visible(&self, entity: Entity) -> bool {
+        self.targetables
+            .get(entity)
+            .is_ok_and(|(transform, targetable)| {
+                let pos = transform.translation.truncate();
+
+                !targetable.stealth
+                    || self.detectors.iter().any(|(detector_transform, detector)| {
+                        detector_transform.translation.truncate().distance(pos) <= detector.radius
+                    })
+            })
+    }
+}
+
+pub fn compute_target(
+    index: Res<SpatialIndex>,
+    detection: Detection,
+    mut shooters: Query<(&Transform, &mut Shooter, Option<&Disabled>)>,
+    targetables: Query<&Transform, With<Targetable>>,
+) {
+    for (transform, mut shooter, disabled) in shooters.iter_mut() {
+        let shooter_pos = transform.translation.truncate();
+
+        // without a target nothing gets fired and running beams stop
+        if disabled.is_some() {
+            shooter.target = None;
+            continue;
+        }
 
-            if shooter_to_targetable < shooter_to_current {
-                shortest = Some(targetable_transform.translation);
+        // keep the lock on the current target while it is alive, visible and in range
+        if let Some(target) = shooter.target {
+            if let Ok(target_transform) = targetables.get(target) {
+                if detection.visible(target)
+                    && shooter.in_range(&shooter_pos, &target_transform.translation)
+                {
+                    continue;
+                }
             }
-        } else {
-            shortest = Some(targetable_transform.translation);
         }
+
+        shooter.target = index.closest(shooter_pos, shooter.range, |entity| {
+            detection.visible(entity)
+        });
     }
+}
 
-    shortest
+#[derive(Component, Default)]
+pub struct Targetable {
+    pub stealth: bool, // hidden from towers unless a detector sees it
 }
 
+// anything the player placed on the map, whether it shoots or not
 #[derive(Component)]
-pub struct Targetable;
+pub struct Tower;
+
+// can't pick targets until the timer runs out
+#[derive(Component)]
+pub struct Disabled {
+    pub timer: Timer,
+}
+
+fn expire_disabled(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    mut disabled: Query<(Entity, &mut Disabled)>,
+) {
+    for (entity, mut disabled) in disabled.iter_mut() {
+        if disabled.timer.tick(clock.delta()).finished() {
+            commands.entity(entity).remove::<Disabled>();
+        }
+    }
+}
+
+// reveals stealthy targetables within the radius to every tower
+#[derive(Component)]
+pub struct Detector {
+    pub radius: f32,
+}
 
 pub struct ShootingPlugin;
 
 impl Plugin for ShootingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(update_shootable_position)
-            .add_system(compute_target)
-            .add_system(shoot_bullet)
-            .add_system(shoot_bomb)
-            .add_system(explode_bomb)
+        app.add_event::<ShotFired>()
+            .init_resource::<StatsDirty>()
+            // after the simulation, removals are forgotten once `CoreStage::Last` starts
+            .add_system_to_stage(CoreStage::PostUpdate, track_removed_stats)
+            // homing projectiles steer towards where the agents moved to this step
+            .add_simulation_system_to_stage(
+                SimulationStage::Movement,
+                steer_homing.after(follow_path),
+            )
+            .add_simulation_system_to_stage(
+                SimulationStage::Movement,
+                update_shootable_position.after(steer_homing),
+            )
+            .add_simulation_system_set(
+                SystemSet::new()
+                    .with_system(expire_disabled.before(ShootingSystem::Targeting))
+                    .with_system(compute_target.label(ShootingSystem::Targeting))
+                    .with_system(capture_base_stats.before(apply_auras))
+                    .with_system(
+                        apply_auras
+                            .before(ShootingSystem::Targeting)
+                            .before(ShootingSystem::Readiness),
+                    )
+                    .with_system(tick_weapons.label(ShootingSystem::Readiness))
+                    .with_system(
+                        explode_bomb
+                            .after(ShootingSystem::Fire)
+                            .before(HealthSystem::Compute),
+                    ),
+            )
+            // one after the other, so shots and damage always come in the same order
+            .add_simulation_system_set(
+                SystemSet::new()
+                    .label(ShootingSystem::Fire)
+                    .after(ShootingSystem::Targeting)
+                    .after(ShootingSystem::Readiness)
+                    .before(HealthSystem::Compute)
+                    .with_system(shoot_bullet)
+                    .with_system(shoot_bomb.after(shoot_bullet))
+                    .with_system(shoot_homing.after(shoot_bomb))
+                    .with_system(shoot_chain.after(shoot_homing))
+                    .with_system(update_beams.after(shoot_chain))
+                    .with_system(fire_beams.after(update_beams)),
+            )
             .register_type::<Shootable>();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOOTER: Vec2 = Vec2::ZERO;
+    const TARGET: Vec2 = Vec2::new(100., 0.);
+    const SPEED: f32 = 50.;
+
+    #[test]
+    fn hits_a_standing_target_where_it_stands() {
+        assert_eq!(
+            intercept_point(SHOOTER, TARGET, Vec2::ZERO, SPEED),
+            Some(TARGET)
+        );
+    }
+
+    #[test]
+    fn never_catches_a_faster_receding_target() {
+        let velocity = Vec2::new(2. * SPEED, 0.);
+
+        assert_eq!(intercept_point(SHOOTER, TARGET, velocity, SPEED), None);
+    }
+
+    #[test]
+    fn meets_a_target_as_fast_as_the_projectile_head_on() {
+        let velocity = Vec2::new(-SPEED, 0.);
+
+        // both close in at the same speed and meet halfway
+        assert_eq!(
+            intercept_point(SHOOTER, TARGET, velocity, SPEED),
+            Some(Vec2::new(50., 0.))
+        );
+    }
+
+    #[test]
+    fn never_catches_a_target_as_fast_as_the_projectile_moving_sideways() {
+        let velocity = Vec2::new(0., SPEED);
+
+        assert_eq!(intercept_point(SHOOTER, TARGET, velocity, SPEED), None);
+    }
+
+    #[test]
+    fn leads_a_crossing_target() {
+        let velocity = Vec2::new(0., 30.);
+
+        let intercept = intercept_point(SHOOTER, TARGET, velocity, SPEED).unwrap();
+        let time = (intercept - TARGET).length() / velocity.length();
+
+        assert!(intercept.y > 0.);
+        assert!((intercept.distance(SHOOTER) - SPEED * time).abs() < 1e-3);
+    }
+}
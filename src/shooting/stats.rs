@@ -0,0 +1,226 @@
+use std::{mem, time::Duration};
+
+use bevy::prelude::*;
+
+use super::{weapon::Weapon, Shooter};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Stats {
+    pub range: f32,
+    pub cooldown: Duration,
+    pub damage_multiplier: f32,
+}
+
+// stats of the tower itself, before any aura is applied; upgrades change these
+#[derive(Component)]
+pub struct BaseStats(pub Stats);
+
+// bonuses as fractions of the base stat, 0.2 is +20%
+#[derive(Clone, Copy, Default)]
+pub struct StatModifiers {
+    pub range: f32,
+    pub fire_rate: f32,
+    pub damage: f32,
+}
+
+impl StatModifiers {
+    fn add(&mut self, other: &StatModifiers) {
+        self.range += other.range;
+        self.fire_rate += other.fire_rate;
+        self.damage += other.damage;
+    }
+
+    fn apply(&self, base: &Stats) -> Stats {
+        Stats {
+            range: base.range * (1. + self.range),
+            cooldown: base.cooldown.div_f32(1. + self.fire_rate.max(-0.9)),
+            damage_multiplier: base.damage_multiplier * (1. + self.damage),
+        }
+    }
+}
+
+// boosts every other shooter whose center is within the radius
+#[derive(Component)]
+pub struct Aura {
+    pub radius: f32,
+    pub modifiers: StatModifiers,
+}
+
+// a tower or an aura went away since auras were last applied
+#[derive(Resource, Default)]
+pub struct StatsDirty(pub bool);
+
+// removals are only reported for the frame they happen in, and a frame can go by without
+// a simulation step, at 0x speed or while paused, so they're noted down every frame
+pub fn track_removed_stats(
+    removed_towers: RemovedComponents<BaseStats>,
+    removed_auras: RemovedComponents<Aura>,
+    mut dirty: ResMut<StatsDirty>,
+) {
+    if removed_towers.iter().next().is_some() || removed_auras.iter().next().is_some() {
+        dirty.0 = true;
+    }
+}
+
+// remember what a freshly placed tower started with
+pub fn capture_base_stats(
+    mut commands: Commands,
+    towers: Query<(Entity, &Shooter, &Weapon), Added<Shooter>>,
+) {
+    for (entity, shooter, weapon) in towers.iter() {
+        commands.entity(entity).insert(BaseStats(Stats {
+            range: shooter.range,
+            cooldown: weapon.cooldown,
+            damage_multiplier: weapon.damage_multiplier,
+        }));
+    }
+}
+
+type BuffableTower<'a> = (
+    Entity,
+    &'a Transform,
+    &'a BaseStats,
+    &'a mut Shooter,
+    &'a mut Weapon,
+);
+
+// recalculate effective stats whenever a tower or an aura is placed, sold or upgraded
+pub fn apply_auras(
+    changed_towers: Query<(), Changed<BaseStats>>,
+    changed_auras: Query<(), Changed<Aura>>,
+    mut removed: ResMut<StatsDirty>,
+    mut towers: Query<BuffableTower>,
+    auras: Query<(Entity, &Transform, &Aura)>,
+) {
+    let removed = mem::take(&mut removed.0);
+    let dirty = removed || !changed_towers.is_empty() || !changed_auras.is_empty();
+
+    if !dirty {
+        return;
+    }
+
+    for (entity, transform, base_stats, mut shooter, mut weapon) in towers.iter_mut() {
+        let tower_pos = transform.translation.truncate();
+        let mut modifiers = StatModifiers::default();
+
+        for (aura_entity, aura_transform, aura) in auras.iter() {
+            let in_radius = aura_transform.translation.truncate().distance(tower_pos) < aura.radius;
+
+            if aura_entity != entity && in_radius {
+                modifiers.add(&aura.modifiers);
+            }
+        }
+
+        let effective = modifiers.apply(&base_stats.0);
+        shooter.range = effective.range;
+        weapon.cooldown = effective.cooldown;
+        weapon.damage_multiplier = effective.damage_multiplier;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::schedule::ShouldRun;
+
+    use super::*;
+
+    // stands in for the simulation, which doesn't step on every frame
+    #[derive(Resource)]
+    struct Stepping(bool);
+
+    fn stepping(stepping: Res<Stepping>) -> ShouldRun {
+        if stepping.0 {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.insert_resource(Stepping(true))
+            .init_resource::<StatsDirty>()
+            .add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(stepping)
+                    .with_system(capture_base_stats.before(apply_auras))
+                    .with_system(apply_auras),
+            )
+            .add_system_to_stage(CoreStage::PostUpdate, track_removed_stats);
+        app
+    }
+
+    fn spawn_tower(app: &mut App, x: f32) -> Entity {
+        app.world
+            .spawn((
+                Transform::from_xyz(x, 0., 0.),
+                Shooter {
+                    range: 100.,
+                    target: None,
+                    lead: false,
+                },
+                Weapon::new(Duration::from_millis(1000)),
+            ))
+            .id()
+    }
+
+    fn spawn_support(app: &mut App, x: f32) -> Entity {
+        app.world
+            .spawn((
+                Transform::from_xyz(x, 0., 0.),
+                Aura {
+                    radius: 50.,
+                    modifiers: StatModifiers {
+                        range: 0.5,
+                        fire_rate: 1.,
+                        damage: 0.,
+                    },
+                },
+            ))
+            .id()
+    }
+
+    fn stats(app: &App, tower: Entity) -> (f32, Duration) {
+        let shooter = app.world.get::<Shooter>(tower).unwrap();
+        let weapon = app.world.get::<Weapon>(tower).unwrap();
+        (shooter.range, weapon.cooldown)
+    }
+
+    #[test]
+    fn buffs_towers_within_the_radius() {
+        let mut app = app();
+        let near = spawn_tower(&mut app, 0.);
+        let far = spawn_tower(&mut app, 200.);
+        spawn_support(&mut app, 20.);
+
+        // base stats are captured on the first update and buffed on the next
+        app.update();
+        app.update();
+
+        assert_eq!(stats(&app, near), (150., Duration::from_millis(500)));
+        assert_eq!(stats(&app, far), (100., Duration::from_millis(1000)));
+    }
+
+    #[test]
+    fn selling_a_support_tower_restores_base_stats() {
+        let mut app = app();
+        let tower = spawn_tower(&mut app, 0.);
+        let support = spawn_support(&mut app, 20.);
+        app.update();
+        app.update();
+
+        // sold in a frame without a step, the removal has to outlive that frame
+        app.world.resource_mut::<Stepping>().0 = false;
+        app.world.despawn(support);
+        app.update();
+        app.update();
+        assert_eq!(stats(&app, tower), (150., Duration::from_millis(500)));
+
+        app.world.resource_mut::<Stepping>().0 = true;
+        app.update();
+
+        let base = app.world.get::<BaseStats>(tower).unwrap().0;
+        assert_eq!(stats(&app, tower), (base.range, base.cooldown));
+        assert_eq!(stats(&app, tower), (100., Duration::from_millis(1000)));
+    }
+}
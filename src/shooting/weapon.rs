@@ -0,0 +1,212 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::clock::GameClock;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WeaponState {
+    Ready,
+    Charging,
+    Reloading,
+}
+
+#[derive(Clone, Copy)]
+pub struct Burst {
+    pub shots: u32,
+    pub interval: Duration,
+}
+
+#[derive(Clone, Copy)]
+pub struct Magazine {
+    pub capacity: u32,
+    pub rounds: u32,
+    pub reload: Duration,
+}
+
+// readiness of a shooter: stays ready until it fires, then charges for the next shot
+#[derive(Component)]
+pub struct Weapon {
+    pub cooldown: Duration,
+    pub burst: Burst,
+    pub magazine: Option<Magazine>,
+    pub damage_multiplier: f32, // effective multiplier for the tower's damage, set by auras
+    state: WeaponState,
+    timer: Timer,
+    burst_shots_fired: u32,
+}
+
+impl Weapon {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            burst: Burst {
+                shots: 1,
+                interval: Duration::ZERO,
+            },
+            magazine: None,
+            damage_multiplier: 1.,
+            state: WeaponState::Ready,
+            timer: Timer::new(Duration::ZERO, TimerMode::Once),
+            burst_shots_fired: 0,
+        }
+    }
+
+    pub fn with_burst(mut self, shots: u32, interval: Duration) -> Self {
+        self.burst = Burst {
+            shots: shots.max(1),
+            interval,
+        };
+        self
+    }
+
+    pub fn with_magazine(mut self, capacity: u32, reload: Duration) -> Self {
+        let capacity = capacity.max(1);
+        self.magazine = Some(Magazine {
+            capacity,
+            rounds: capacity,
+            reload,
+        });
+        self
+    }
+
+    pub fn state(&self) -> WeaponState {
+        self.state
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.state == WeaponState::Ready
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        if self.state == WeaponState::Ready {
+            return;
+        }
+
+        if self.timer.tick(delta).finished() {
+            if self.state == WeaponState::Reloading {
+                if let Some(magazine) = &mut self.magazine {
+                    magazine.rounds = magazine.capacity;
+                }
+            }
+
+            self.state = WeaponState::Ready;
+        }
+    }
+
+    pub fn fire(&mut self) {
+        self.burst_shots_fired += 1;
+
+        let empty = match &mut self.magazine {
+            Some(magazine) => {
+                magazine.rounds = magazine.rounds.saturating_sub(1);
+                magazine.rounds == 0
+            }
+            None => false,
+        };
+
+        if empty {
+            self.burst_shots_fired = 0;
+            let reload = self.magazine.map_or(Duration::ZERO, |m| m.reload);
+            self.charge(WeaponState::Reloading, reload);
+        } else if self.burst_shots_fired < self.burst.shots {
+            self.charge(WeaponState::Charging, self.burst.interval);
+        } else {
+            self.burst_shots_fired = 0;
+            self.charge(WeaponState::Charging, self.cooldown);
+        }
+    }
+
+    fn charge(&mut self, state: WeaponState, duration: Duration) {
+        self.state = state;
+        self.timer = Timer::new(duration, TimerMode::Once);
+    }
+}
+
+pub struct ShotFired {
+    pub shooter: Entity,
+    pub target: Entity,
+    pub origin: Vec3,
+}
+
+pub fn tick_weapons(clock: Res<GameClock>, mut weapons: Query<&mut Weapon>) {
+    for mut weapon in weapons.iter_mut() {
+        weapon.tick(clock.delta());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_millis(100);
+
+    fn rounds(weapon: &Weapon) -> u32 {
+        weapon.magazine.unwrap().rounds
+    }
+
+    #[test]
+    fn charges_for_the_cooldown_after_firing() {
+        let mut weapon = Weapon::new(COOLDOWN);
+        assert!(weapon.is_ready());
+
+        weapon.fire();
+        assert_eq!(weapon.state(), WeaponState::Charging);
+
+        weapon.tick(COOLDOWN - Duration::from_millis(1));
+        assert_eq!(weapon.state(), WeaponState::Charging);
+
+        weapon.tick(Duration::from_millis(1));
+        assert!(weapon.is_ready());
+    }
+
+    #[test]
+    fn bursts_use_the_interval_then_the_cooldown() {
+        let interval = Duration::from_millis(10);
+        let mut weapon = Weapon::new(COOLDOWN).with_burst(3, interval);
+
+        for _ in 0..2 {
+            weapon.fire();
+            weapon.tick(interval);
+            assert!(weapon.is_ready());
+        }
+
+        // the last shot of the burst waits for the whole cooldown
+        weapon.fire();
+        weapon.tick(interval);
+        assert!(!weapon.is_ready());
+
+        weapon.tick(COOLDOWN - interval);
+        assert!(weapon.is_ready());
+    }
+
+    #[test]
+    fn reloads_an_empty_magazine() {
+        let reload = Duration::from_millis(500);
+        let mut weapon = Weapon::new(COOLDOWN).with_magazine(2, reload);
+
+        weapon.fire();
+        assert_eq!(weapon.state(), WeaponState::Charging);
+        assert_eq!(rounds(&weapon), 1);
+        weapon.tick(COOLDOWN);
+
+        weapon.fire();
+        assert_eq!(weapon.state(), WeaponState::Reloading);
+        assert_eq!(rounds(&weapon), 0);
+
+        weapon.tick(COOLDOWN);
+        assert_eq!(weapon.state(), WeaponState::Reloading);
+
+        weapon.tick(reload - COOLDOWN);
+        assert!(weapon.is_ready());
+        assert_eq!(rounds(&weapon), 2);
+    }
+
+    #[test]
+    fn magazine_holds_at_least_one_round() {
+        let weapon = Weapon::new(COOLDOWN).with_magazine(0, COOLDOWN);
+
+        assert_eq!(weapon.magazine.unwrap().capacity, 1);
+        assert_eq!(rounds(&weapon), 1);
+    }
+}
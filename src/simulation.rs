@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::schedule::{IntoSystemDescriptor, ShouldRun},
+    prelude::*,
+    time::TimeSystem,
+    transform::TransformSystem,
+};
+
+use crate::{
+    clock::GameClock, damage_numbers::FloatingText, grid::agent::GridAgent, shooting::Shootable,
+    state::GameState,
+};
+
+// gameplay runs in its own stage after `CoreStage::Update`, once per fixed step
+#[derive(StageLabel)]
+pub struct Simulation;
+
+#[derive(StageLabel, Clone, Copy)]
+pub enum SimulationStage {
+    Prepare,  // spatial index and interpolation bookkeeping
+    Movement, // agents, projectiles and floating text
+    Update,   // targeting, shooting, damage, lifetimes and spawning
+    Finish,
+}
+
+// splitmix64, every random draw of a run comes from here so the seed reproduces it
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    state: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // every level starts the sequence over
+    pub fn reset(&mut self) {
+        self.state = self.seed;
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+}
+
+// rendered position is blended between the last two steps
+#[derive(Component)]
+pub struct Interpolated {
+    previous: Vec3,
+    current: Vec3,
+}
+
+type Moving = Or<(With<GridAgent>, With<Shootable>, With<FloatingText>)>;
+
+pub trait SimulationApp {
+    fn add_simulation_system_set(&mut self, system_set: SystemSet) -> &mut Self;
+    fn add_simulation_system_to_stage<Params>(
+        &mut self,
+        stage: SimulationStage,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self;
+}
+
+impl SimulationApp for App {
+    fn add_simulation_system_set(&mut self, system_set: SystemSet) -> &mut Self {
+        self.stage(Simulation, |schedule: &mut Schedule| {
+            schedule.add_system_set_to_stage(SimulationStage::Update, system_set)
+        })
+    }
+
+    fn add_simulation_system_to_stage<Params>(
+        &mut self,
+        stage: SimulationStage,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self {
+        self.stage(Simulation, |schedule: &mut Schedule| {
+            schedule.add_system_to_stage(stage, system)
+        })
+    }
+}
+
+fn tick_clock(time: Res<Time>, state: Res<State<GameState>>, mut clock: ResMut<GameClock>) {
+    if *state.current() == GameState::Playing {
+        clock.accumulate(time.delta());
+    }
+}
+
+fn run_step(state: Res<State<GameState>>, mut clock: ResMut<GameClock>) -> ShouldRun {
+    if *state.current() == GameState::Playing && clock.consume_step() {
+        ShouldRun::YesAndCheckAgain
+    } else {
+        ShouldRun::No
+    }
+}
+
+// undo last frame's blending before the simulation moves anything
+fn restore_positions(mut entities: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in entities.iter_mut() {
+        transform.translation = interpolated.current;
+    }
+}
+
+fn record_previous(mut entities: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in entities.iter_mut() {
+        interpolated.previous = transform.translation;
+    }
+}
+
+fn record_current(mut entities: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in entities.iter_mut() {
+        interpolated.current = transform.translation;
+    }
+}
+
+fn track_moving(
+    mut commands: Commands,
+    entities: Query<(Entity, &Transform), (Moving, Without<Interpolated>)>,
+) {
+    for (entity, transform) in entities.iter() {
+        commands.entity(entity).insert(Interpolated {
+            previous: transform.translation,
+            current: transform.translation,
+        });
+    }
+}
+
+fn interpolate_positions(
+    clock: Res<GameClock>,
+    mut entities: Query<(&mut Transform, &Interpolated)>,
+) {
+    let alpha = clock.alpha();
+
+    for (mut transform, interpolated) in entities.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
+    }
+}
+
+pub struct SimulationPlugin {
+    pub steps_per_second: u32,
+    pub seed: u64,
+    pub lockstep: bool, // exactly one step per app update, for headless runs and benchmarks
+}
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        let step = Duration::from_secs_f64(1. / self.steps_per_second as f64);
+
+        // single threaded, the order of systems that touch the same data is made explicit
+        // everywhere since ambiguous ones aren't guaranteed to run in the same order every run
+        let mut schedule = Schedule::default().with_run_criteria(run_step);
+        schedule
+            .add_stage(SimulationStage::Prepare, SystemStage::single_threaded())
+            .add_stage_after(
+                SimulationStage::Prepare,
+                SimulationStage::Movement,
+                SystemStage::single_threaded(),
+            )
+            .add_stage_after(
+                SimulationStage::Movement,
+                SimulationStage::Update,
+                SystemStage::single_threaded(),
+            )
+            .add_stage_after(
+                SimulationStage::Update,
+                SimulationStage::Finish,
+                SystemStage::single_threaded(),
+            );
+
+        app.insert_resource(GameClock::new(step, self.lockstep))
+            .insert_resource(GameRng::new(self.seed))
+            .add_stage_after(CoreStage::Update, Simulation, schedule)
+            .add_system_to_stage(CoreStage::First, tick_clock.after(TimeSystem))
+            .add_system_to_stage(CoreStage::PreUpdate, restore_positions)
+            .add_simulation_system_to_stage(SimulationStage::Prepare, record_previous)
+            .add_simulation_system_to_stage(SimulationStage::Finish, record_current)
+            .add_simulation_system_to_stage(SimulationStage::Finish, track_moving)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                interpolate_positions.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
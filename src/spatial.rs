@@ -0,0 +1,142 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    health::Damageable,
+    shooting::Targetable,
+    simulation::{SimulationApp, SimulationStage},
+};
+
+#[derive(Clone, Copy)]
+pub struct SpatialEntry {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+// uniform hash grid of everything that can be targeted or damaged, rebuilt every step
+#[derive(Resource)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<SpatialEntry>>,
+    max_half_size: Vec2, // entries are bucketed by center, queries grow by this much
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+            max_half_size: Vec2::ZERO,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        // keep the allocated buckets around, the same cells get filled again next step
+        for entries in self.cells.values_mut() {
+            entries.clear();
+        }
+        self.max_half_size = Vec2::ZERO;
+    }
+
+    pub fn insert(&mut self, entry: SpatialEntry) {
+        self.max_half_size = self.max_half_size.max(entry.size / 2.);
+        self.cells
+            .entry(self.cell_of(entry.position))
+            .or_default()
+            .push(entry);
+    }
+
+    // entries whose center is closer than `radius` to `center`
+    pub fn query_radius(&self, center: Vec2, radius: f32) -> impl Iterator<Item = &SpatialEntry> {
+        let extent = Vec2::splat(radius);
+
+        self.entries_between(center - extent, center + extent)
+            .filter(move |entry| entry.position.distance(center) < radius)
+    }
+
+    // entries whose bounding box overlaps the box with the given center and size
+    pub fn query_aabb(&self, center: Vec2, size: Vec2) -> impl Iterator<Item = &SpatialEntry> {
+        let half_size = size / 2.;
+        let extent = half_size + self.max_half_size;
+
+        self.entries_between(center - extent, center + extent)
+            .filter(move |entry| {
+                let offset = (entry.position - center).abs();
+                let reach = half_size + entry.size / 2.;
+
+                offset.x < reach.x && offset.y < reach.y
+            })
+    }
+
+    pub fn closest(
+        &self,
+        center: Vec2,
+        radius: f32,
+        filter: impl Fn(Entity) -> bool,
+    ) -> Option<Entity> {
+        let mut closest: Option<(Entity, f32)> = None;
+
+        for entry in self.query_radius(center, radius) {
+            if !filter(entry.entity) {
+                continue;
+            }
+
+            let distance = entry.position.distance(center);
+
+            if let Some((_, closest_distance)) = closest {
+                if distance < closest_distance {
+                    closest = Some((entry.entity, distance));
+                }
+            } else {
+                closest = Some((entry.entity, distance));
+            }
+        }
+
+        closest.map(|(entity, _)| entity)
+    }
+
+    fn cell_of(&self, position: Vec2) -> (i32, i32) {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn entries_between(&self, min: Vec2, max: Vec2) -> impl Iterator<Item = &SpatialEntry> {
+        let (min_x, min_y) = self.cell_of(min);
+        let (max_x, max_y) = self.cell_of(max);
+
+        (min_x..=max_x)
+            .flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+}
+
+type Indexed = Or<(With<Targetable>, With<Damageable>)>;
+
+fn update_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    entities: Query<(Entity, &Transform), Indexed>,
+) {
+    index.clear();
+
+    for (entity, transform) in entities.iter() {
+        index.insert(SpatialEntry {
+            entity,
+            position: transform.translation.truncate(),
+            size: transform.scale.truncate(),
+        });
+    }
+}
+
+pub struct SpatialPlugin {
+    pub cell_size: f32,
+}
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SpatialIndex::new(self.cell_size))
+            .add_simulation_system_to_stage(SimulationStage::Prepare, update_spatial_index);
+    }
+}
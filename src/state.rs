@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{
+    clock::{GameClock, GameSpeed},
+    enemy::Enemy,
+    grid::agent::GridAgent,
+    health::HealthSystem,
+    lifetime::Lifetime,
+    score::Score,
+    shooting::{beam::Beam, Shootable, Tower},
+    simulation::{GameRng, SimulationApp},
+    wave::{spawn_wave, SpawnedEnemies, WaveScript},
+};
+
+const STARTING_LIVES: u32 = 20;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameState {
+    MainMenu,
+    LevelSelect,
+    Playing,
+    Paused, // pushed on top of playing, so the level is kept underneath
+    Victory,
+    GameOver,
+}
+
+#[derive(Resource)]
+pub struct Lives(pub u32);
+
+// how the last level ended, only there once it did
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Victory,
+    Defeat,
+}
+
+// everything that belongs to a running level, the grid cleans up after itself
+type LevelEntity = Or<(
+    With<Enemy>,
+    With<Tower>,
+    With<Shootable>,
+    With<Beam>,
+    With<Lifetime>,
+)>;
+
+fn start_level(mut commands: Commands, mut clock: ResMut<GameClock>, mut rng: ResMut<GameRng>) {
+    clock.set_speed(GameSpeed::Normal);
+    rng.reset();
+    commands.insert_resource(Lives(STARTING_LIVES));
+    commands.remove_resource::<Outcome>();
+}
+
+// enemies that ran out of path made it through
+fn leak_enemies(
+    mut commands: Commands,
+    mut lives: ResMut<Lives>,
+    mut score: ResMut<Score>,
+    enemies: Query<(Entity, &GridAgent), With<Enemy>>,
+) {
+    for (entity, agent) in enemies.iter() {
+        if agent.path.is_none() {
+            lives.0 = lives.0.saturating_sub(1);
+            score.leaks += 1;
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn check_level_end(
+    mut commands: Commands,
+    mut state: ResMut<State<GameState>>,
+    lives: Res<Lives>,
+    wave: Res<WaveScript>,
+    spawned: Res<SpawnedEnemies>,
+    enemies: Query<(), With<Enemy>>,
+) {
+    let (outcome, next) = if lives.0 == 0 {
+        (Outcome::Defeat, GameState::GameOver)
+    } else if spawned.0 >= wave.len() && enemies.is_empty() {
+        (Outcome::Victory, GameState::Victory)
+    } else {
+        return;
+    };
+
+    commands.insert_resource(outcome);
+    // a transition might already be queued by the player
+    let _ = state.set(next);
+}
+
+fn cleanup_level(mut commands: Commands, entities: Query<Entity, LevelEntity>) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct StatePlugin;
+
+impl Plugin for StatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state(GameState::MainMenu)
+            .insert_resource(Lives(STARTING_LIVES))
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(start_level))
+            .add_simulation_system_set(
+                SystemSet::new()
+                    .with_system(leak_enemies.after(HealthSystem::Death))
+                    .with_system(check_level_end.after(leak_enemies).after(spawn_wave)),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(cleanup_level));
+    }
+}
@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    clock::GameClock,
+    grid::agent::GridAgent,
+    health::{damage::DamageKind, DamageDealer, DamageEvent, HealthSystem, Hit},
+    simulation::SimulationApp,
+};
+
+const DAMAGE_TICK: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EffectKind {
+    Slow,
+    Stun,
+    Burn,
+    Poison,
+}
+
+#[derive(Clone, Copy)]
+pub enum Stacking {
+    Refresh,            // a new application restarts the running effect
+    Stack { max: u32 }, // runs side by side with the others, up to `max` at once
+    Strongest,          // only the effect with the highest magnitude is kept
+}
+
+#[derive(Clone, Copy)]
+pub struct StatusEffect {
+    pub kind: EffectKind,
+    pub magnitude: f32, // share of speed removed for slows, damage per second for burn and poison
+    pub duration: Duration,
+    pub stacking: Stacking,
+    pub source: Option<Entity>,
+}
+
+struct ActiveEffect {
+    effect: StatusEffect,
+    timer: Timer,
+    damage_tick: Timer,
+}
+
+impl ActiveEffect {
+    fn new(effect: StatusEffect) -> Self {
+        Self {
+            effect,
+            timer: Timer::new(effect.duration, TimerMode::Once),
+            damage_tick: Timer::new(DAMAGE_TICK, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct StatusEffects {
+    active: Vec<ActiveEffect>,
+}
+
+impl StatusEffects {
+    pub fn has(&self, kind: EffectKind) -> bool {
+        self.active.iter().any(|active| active.effect.kind == kind)
+    }
+
+    fn apply(&mut self, effect: StatusEffect) {
+        let stacks = self.count(effect.kind);
+        let mut same_kind = self
+            .active
+            .iter_mut()
+            .filter(|active| active.effect.kind == effect.kind);
+
+        match effect.stacking {
+            Stacking::Refresh => match same_kind.next() {
+                Some(active) => *active = ActiveEffect::new(effect),
+                None => self.active.push(ActiveEffect::new(effect)),
+            },
+            Stacking::Stack { max } => {
+                if stacks < max as usize {
+                    self.active.push(ActiveEffect::new(effect));
+                } else if let Some(oldest) = same_kind.min_by_key(|active| active.timer.remaining())
+                {
+                    *oldest = ActiveEffect::new(effect);
+                }
+            }
+            Stacking::Strongest => match same_kind.next() {
+                Some(active) if effect.magnitude >= active.effect.magnitude => {
+                    *active = ActiveEffect::new(effect)
+                }
+                Some(_) => {}
+                None => self.active.push(ActiveEffect::new(effect)),
+            },
+        }
+    }
+
+    fn count(&self, kind: EffectKind) -> usize {
+        self.active
+            .iter()
+            .filter(|active| active.effect.kind == kind)
+            .count()
+    }
+
+    // multiplier for the agent's base speed
+    fn speed_factor(&self) -> f32 {
+        if self.has(EffectKind::Stun) {
+            return 0.;
+        }
+
+        self.active
+            .iter()
+            .filter(|active| active.effect.kind == EffectKind::Slow)
+            .map(|active| 1. - active.effect.magnitude.clamp(0., 1.))
+            .product()
+    }
+}
+
+#[derive(Component, Clone, Copy, Default)]
+pub struct StatusImmunity {
+    pub slow: bool,
+    pub stun: bool,
+    pub burn: bool,
+    pub poison: bool,
+}
+
+impl StatusImmunity {
+    pub fn immune_to(&self, kind: EffectKind) -> bool {
+        match kind {
+            EffectKind::Slow => self.slow,
+            EffectKind::Stun => self.stun,
+            EffectKind::Burn => self.burn,
+            EffectKind::Poison => self.poison,
+        }
+    }
+}
+
+// effects applied to whatever a damage dealer hits
+#[derive(Component, Clone)]
+pub struct StatusPayload(pub Vec<StatusEffect>);
+
+pub struct ApplyStatus {
+    pub target: Entity,
+    pub effect: StatusEffect,
+}
+
+#[derive(SystemLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatusSystem {
+    Inflict,
+    Receive,
+}
+
+fn inflict_payloads(
+    mut hits: EventReader<Hit>,
+    mut apply_status: EventWriter<ApplyStatus>,
+    payloads: Query<(&StatusPayload, Option<&DamageDealer>)>,
+) {
+    for hit in hits.iter() {
+        if let Ok((payload, damage_dealer)) = payloads.get(hit.dealer) {
+            for effect in payload.0.iter() {
+                apply_status.send(ApplyStatus {
+                    target: hit.target,
+                    effect: StatusEffect {
+                        source: damage_dealer.and_then(|dealer| dealer.source),
+                        ..*effect
+                    },
+                });
+            }
+        }
+    }
+}
+
+fn receive_status(
+    mut apply_status: EventReader<ApplyStatus>,
+    mut targets: Query<(&mut StatusEffects, Option<&StatusImmunity>)>,
+) {
+    for event in apply_status.iter() {
+        if let Ok((mut effects, immunity)) = targets.get_mut(event.target) {
+            if immunity.is_some_and(|immunity| immunity.immune_to(event.effect.kind)) {
+                continue;
+            }
+
+            effects.apply(event.effect);
+        }
+    }
+}
+
+fn tick_status_effects(
+    clock: Res<GameClock>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut targets: Query<(Entity, &mut StatusEffects)>,
+) {
+    for (entity, mut effects) in targets.iter_mut() {
+        for active in effects.active.iter_mut() {
+            active.timer.tick(clock.delta());
+
+            let damage_kind = match active.effect.kind {
+                EffectKind::Burn => DamageKind::Fire,
+                EffectKind::Poison => DamageKind::Poison,
+                EffectKind::Slow | EffectKind::Stun => continue,
+            };
+
+            let ticks = active
+                .damage_tick
+                .tick(clock.delta())
+                .times_finished_this_tick();
+            if ticks > 0 {
+                damage_events.send(DamageEvent {
+                    source: active.effect.source,
+                    target: entity,
+                    amount: active.effect.magnitude * DAMAGE_TICK.as_secs_f32() * ticks as f32,
+                    kind: damage_kind,
+                    crit: None,
+                });
+            }
+        }
+
+        effects.active.retain(|active| !active.timer.finished());
+    }
+}
+
+fn update_agent_speed(mut agents: Query<(&mut GridAgent, &StatusEffects), Changed<StatusEffects>>) {
+    for (mut agent, effects) in agents.iter_mut() {
+        agent.speed = agent.base_speed * effects.speed_factor();
+    }
+}
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyStatus>().add_simulation_system_set(
+            SystemSet::new()
+                .with_system(
+                    inflict_payloads
+                        .label(StatusSystem::Inflict)
+                        .after(HealthSystem::Compute),
+                )
+                .with_system(
+                    receive_status
+                        .label(StatusSystem::Receive)
+                        .after(StatusSystem::Inflict),
+                )
+                .with_system(
+                    tick_status_effects
+                        .after(StatusSystem::Receive)
+                        .before(HealthSystem::Modify),
+                )
+                .with_system(
+                    update_agent_speed
+                        .after(tick_status_effects)
+                        .before(HealthSystem::Modify),
+                ),
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grid::DebugGrid,
+    health::{
+        damage::{Crit, DamageKind},
+        splash::{Falloff, Splash},
+    },
+    score::DamageDealt,
+    shooting::{
+        beam::{BeamShooter, Ramp},
+        bomb::{BombShooter, Lingering},
+        bullet::BulletShooter,
+        chain::ChainShooter,
+        homing::HomingShooter,
+        stats::{Aura, StatModifiers},
+        weapon::Weapon,
+        Detector, Shooter, Tower,
+    },
+    state::GameState,
+    status::{EffectKind, Stacking, StatusEffect, StatusPayload},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum TowerKind {
+    Bomb,
+    Homing,
+    Frost,
+    Poison,
+    Laser,
+    Tesla,
+    Support, // doesn't shoot, makes the towers next to it better
+    Radar,   // doesn't shoot either, lets every tower see stealthy enemies close to it
+}
+
+impl TowerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TowerKind::Bomb => "Bomb Tower",
+            TowerKind::Homing => "Homing Tower",
+            TowerKind::Frost => "Frost Tower",
+            TowerKind::Poison => "Poison Tower",
+            TowerKind::Laser => "Laser Tower",
+            TowerKind::Tesla => "Tesla Tower",
+            TowerKind::Support => "Support Tower",
+            TowerKind::Radar => "Radar Tower",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            TowerKind::Bomb => Color::GREEN,
+            TowerKind::Homing => Color::ORANGE_RED,
+            TowerKind::Frost => Color::CYAN,
+            TowerKind::Poison => Color::LIME_GREEN,
+            TowerKind::Laser => Color::PURPLE,
+            TowerKind::Tesla => Color::ALICE_BLUE,
+            TowerKind::Support => Color::GOLD,
+            TowerKind::Radar => Color::TEAL,
+        }
+    }
+}
+
+// which tower stands on which grid cell
+#[derive(Component, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TowerPlacement {
+    pub kind: TowerKind,
+    pub x: usize,
+    pub y: usize,
+}
+
+// towers placed when a level starts, made for the full size map
+#[derive(Resource, Clone)]
+pub struct TowerLayout(pub Vec<TowerPlacement>);
+
+impl Default for TowerLayout {
+    fn default() -> Self {
+        let placements = [
+            (TowerKind::Bomb, 33, 20),
+            (TowerKind::Homing, 20, 15),
+            (TowerKind::Frost, 10, 21),
+            (TowerKind::Poison, 45, 15),
+            (TowerKind::Laser, 54, 21),
+            (TowerKind::Tesla, 27, 15),
+            (TowerKind::Support, 24, 17),
+            (TowerKind::Radar, 40, 20),
+        ];
+
+        Self(
+            placements
+                .into_iter()
+                .map(|(kind, x, y)| TowerPlacement { kind, x, y })
+                .collect(),
+        )
+    }
+}
+
+pub fn spawn(commands: &mut Commands, grid: &DebugGrid, placement: TowerPlacement) -> Entity {
+    let pos = grid.to_screen_coords(placement.x, placement.y);
+
+    let mut tower = commands.spawn((
+        Name::new(placement.kind.name()),
+        SpriteBundle {
+            sprite: Sprite {
+                color: placement.kind.color(),
+                ..default()
+            },
+            transform: Transform::from_xyz(pos.x, pos.y, 1.).with_scale(Vec3::new(15., 15., 1.)),
+            ..default()
+        },
+        Tower,
+        placement,
+        DamageDealt::default(),
+    ));
+
+    match placement.kind {
+        TowerKind::Bomb => tower.insert((
+            Shooter {
+                target: None,
+                range: 300.,
+                lead: true,
+            },
+            Weapon::new(Duration::from_millis(1000)),
+            BombShooter {
+                splash: Splash {
+                    radius: 50.,
+                    damage: 50.,
+                    kind: DamageKind::Explosive,
+                    falloff: Falloff::Linear,
+                },
+                lingering: Some(Lingering {
+                    size: 40.,
+                    damage: 4.,
+                    kind: DamageKind::Fire,
+                    interval: Duration::from_millis(500),
+                    duration: Duration::from_secs(3),
+                }),
+                ground_impact: false,
+            },
+        )),
+        TowerKind::Homing => tower.insert((
+            Shooter {
+                target: None,
+                range: 250.,
+                lead: false,
+            },
+            Weapon::new(Duration::from_millis(2000)).with_burst(3, Duration::from_millis(150)),
+            HomingShooter {
+                crit: Some(Crit {
+                    chance: 0.2,
+                    multiplier: 2.,
+                }),
+            },
+        )),
+        TowerKind::Frost => tower.insert((
+            Shooter {
+                target: None,
+                range: 200.,
+                lead: true,
+            },
+            Weapon::new(Duration::from_millis(800)).with_magazine(6, Duration::from_secs(3)),
+            BulletShooter {
+                damage: 4.,
+                kind: DamageKind::Ice,
+            },
+            StatusPayload(vec![StatusEffect {
+                kind: EffectKind::Slow,
+                magnitude: 0.4,
+                duration: Duration::from_secs(2),
+                stacking: Stacking::Strongest,
+                source: None,
+            }]),
+        )),
+        TowerKind::Poison => tower.insert((
+            Shooter {
+                target: None,
+                range: 200.,
+                lead: true,
+            },
+            Weapon::new(Duration::from_millis(1200)),
+            BulletShooter {
+                damage: 2.,
+                kind: DamageKind::Poison,
+            },
+            StatusPayload(vec![StatusEffect {
+                kind: EffectKind::Poison,
+                magnitude: 6.,
+                duration: Duration::from_secs(4),
+                stacking: Stacking::Stack { max: 3 },
+                source: None,
+            }]),
+        )),
+        TowerKind::Laser => tower.insert((
+            Shooter {
+                target: None,
+                range: 180.,
+                lead: false,
+            },
+            Weapon::new(Duration::from_millis(500)),
+            BeamShooter {
+                damage_per_second: 20.,
+                kind: DamageKind::Fire,
+                ramp: Some(Ramp {
+                    per_second: 0.5,
+                    max_multiplier: 3.,
+                }),
+            },
+        )),
+        TowerKind::Tesla => tower.insert((
+            Shooter {
+                target: None,
+                range: 220.,
+                lead: false,
+            },
+            Weapon::new(Duration::from_millis(1500)),
+            ChainShooter {
+                damage: 30.,
+                kind: DamageKind::Lightning,
+                jumps: 4,
+                jump_radius: 80.,
+                falloff: 0.7,
+            },
+        )),
+        TowerKind::Support => tower.insert(Aura {
+            radius: 120.,
+            modifiers: StatModifiers {
+                range: 0.15,
+                fire_rate: 0.25,
+                damage: 0.1,
+            },
+        }),
+        TowerKind::Radar => tower.insert(Detector { radius: 200. }),
+    };
+
+    tower.id()
+}
+
+// places the towers as soon as a level's grid is spawned
+pub fn setup_towers(
+    mut commands: Commands,
+    layout: Res<TowerLayout>,
+    grid: Query<&DebugGrid, Added<DebugGrid>>,
+) {
+    let grid = match grid.get_single() {
+        Ok(grid) => grid,
+        Err(_) => return,
+    };
+
+    for placement in layout.0.iter() {
+        spawn(&mut commands, grid, *placement);
+    }
+}
+
+pub struct TowerPlugin;
+
+impl Plugin for TowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TowerLayout>()
+            .add_system_set(SystemSet::on_update(GameState::Playing).with_system(setup_towers));
+    }
+}
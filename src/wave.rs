@@ -0,0 +1,167 @@
+use std::{fs, time::Duration};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    clock::GameClock,
+    enemy::{self, EnemyKind},
+    grid::{a_star::GridCoord, DebugGrid},
+    simulation::SimulationApp,
+    state::GameState,
+};
+
+#[derive(Resource)]
+pub struct SpawnTimer(pub Timer);
+
+#[derive(Resource)]
+pub struct SpawnedEnemies(pub usize);
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct SpawnGroup {
+    pub kind: EnemyKind,
+    pub count: usize,
+}
+
+// enemies of a level in the order they spawn, one every interval
+#[derive(Resource, Clone, Debug, Deserialize)]
+pub struct WaveScript {
+    pub interval: f32, // seconds
+    pub groups: Vec<SpawnGroup>,
+}
+
+impl WaveScript {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let source = fs::read_to_string(path)
+            .map_err(|err| format!("could not read wave file {}: {}", path, err))?;
+
+        let wave: Self =
+            ron::from_str(&source).map_err(|err| format!("invalid wave file {}: {}", path, err))?;
+
+        // the spawn timer can't run with a zero or negative duration
+        if !wave.interval.is_finite() || wave.interval <= 0. {
+            return Err(format!(
+                "invalid wave file {}: interval must be a positive number, got {}",
+                path, wave.interval
+            ));
+        }
+
+        Ok(wave)
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.iter().map(|group| group.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn kind_at(&self, index: usize) -> Option<EnemyKind> {
+        let mut first = 0;
+
+        for group in self.groups.iter() {
+            if index < first + group.count {
+                return Some(group.kind);
+            }
+            first += group.count;
+        }
+
+        None
+    }
+}
+
+// a rotation of every regular enemy, the last one of the wave is a boss
+impl Default for WaveScript {
+    fn default() -> Self {
+        const SPAWN_ORDER: [EnemyKind; 7] = [
+            EnemyKind::Grunt,
+            EnemyKind::Grunt,
+            EnemyKind::Runner,
+            EnemyKind::Healer,
+            EnemyKind::Brute,
+            EnemyKind::Brood,
+            EnemyKind::Shade,
+        ];
+        const WAVE_SIZE: usize = 60;
+
+        let mut groups: Vec<SpawnGroup> = SPAWN_ORDER
+            .iter()
+            .cycle()
+            .take(WAVE_SIZE - 1)
+            .map(|&kind| SpawnGroup { kind, count: 1 })
+            .collect();
+        groups.push(SpawnGroup {
+            kind: EnemyKind::Warlord,
+            count: 1,
+        });
+
+        Self {
+            interval: 0.5,
+            groups,
+        }
+    }
+}
+
+fn spawn_enemy(
+    commands: &mut Commands,
+    grid: &DebugGrid,
+    kind: EnemyKind,
+    grid_pos: (usize, usize),
+    target: GridCoord,
+) {
+    let agent_pos = grid.to_screen_coords(grid_pos.0, grid_pos.1);
+    let agent_pos = Vec3::new(agent_pos.x, agent_pos.y, 10.);
+
+    let grid_pos = (grid_pos.0 as i32, grid_pos.1 as i32);
+    let path = grid.find_path(grid_pos, target);
+    enemy::spawn(commands, kind, agent_pos, path);
+}
+
+fn start_wave(mut commands: Commands, wave: Res<WaveScript>) {
+    commands.insert_resource(SpawnTimer(Timer::new(
+        Duration::from_secs_f32(wave.interval),
+        TimerMode::Repeating,
+    )));
+    commands.insert_resource(SpawnedEnemies(0));
+}
+
+pub(crate) fn spawn_wave(
+    mut timer: ResMut<SpawnTimer>,
+    clock: Res<GameClock>,
+    mut commands: Commands,
+    grid: Query<&DebugGrid>,
+    wave: Res<WaveScript>,
+    mut spawned: ResMut<SpawnedEnemies>,
+) {
+    let grid = grid.single();
+
+    let ticks = timer.0.tick(clock.delta()).times_finished_this_tick();
+
+    for _ in 0..ticks {
+        let kind = match wave.kind_at(spawned.0) {
+            Some(kind) => kind,
+            None => return,
+        };
+        spawned.0 += 1;
+
+        spawn_enemy(
+            &mut commands,
+            grid,
+            kind,
+            (0, grid.size_y / 2),
+            (grid.size_x as i32 - 1, grid.size_y as i32 / 2),
+        );
+    }
+}
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WaveScript>()
+            .insert_resource(SpawnedEnemies(0))
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(start_wave))
+            .add_simulation_system_set(SystemSet::new().with_system(spawn_wave));
+    }
+}
@@ -0,0 +1,11 @@
+use tower_defense::headless::{self, Scenario};
+
+#[test]
+fn same_seed_same_report() {
+    let scenario = Scenario::load("assets/scenarios/open_field.ron").unwrap();
+
+    let first = headless::run(&scenario).unwrap();
+    let second = headless::run(&scenario).unwrap();
+
+    assert_eq!(first, second);
+}